@@ -56,6 +56,7 @@ mod tests {
     mod storage {
         mod database;
         mod packing;
+        mod padding;
         mod storage;
     }
 
@@ -58,6 +58,7 @@ pub mod attacker_model {
                 schema: DojoStoredModelImpl::schema(self),
                 packed_size: Self::packed_size(self),
                 unpacked_size: Self::unpacked_size(self),
+                default_values: [].span(),
             }
         }
     }
@@ -96,6 +96,7 @@ pub mod m_FooInvalidName {
                 schema: StoredModelImpl::schema(self),
                 packed_size: Self::packed_size(self),
                 unpacked_size: Self::unpacked_size(self),
+                default_values: [].span(),
             }
         }
     }
@@ -1,3 +1,4 @@
+use dojo::meta::MemberTrait;
 use dojo::model::{Model, ModelPtr, ModelStorage, ModelValue, ModelValueStorage};
 use dojo::world::WorldStorage;
 use crate::world::{NamespaceDef, TestResource, spawn_test_world};
@@ -61,12 +62,60 @@ struct FooSchema {
     v3: AStruct,
 }
 
+#[derive(Copy, Drop, Serde, Debug, PartialEq, IntrospectPacked)]
+enum Stance {
+    Idle,
+    Guard,
+    Dodge,
+}
+
+#[derive(Copy, Drop, Debug, IntrospectPacked)]
+#[dojo::model]
+struct Narrowed {
+    #[key]
+    id: felt252,
+    #[bits(8)]
+    level: u32,
+    health: u16,
+    #[bits(2)]
+    stance: Stance,
+    #[skip]
+    cached: u32,
+}
+
+#[derive(Copy, Drop, Debug, IntrospectPacked)]
+#[dojo::model]
+struct TooNarrow {
+    #[key]
+    id: felt252,
+    #[bits(1)]
+    stance: Stance,
+}
+
+#[derive(Copy, Drop, Serde, Debug)]
+#[dojo::model]
+struct WithDefaults {
+    #[key]
+    id: felt252,
+    #[default(10)]
+    hp: u32,
+    level: u8,
+}
+
+#[derive(Copy, Drop, Serde, Debug, PartialEq)]
+#[dojo::model]
+struct Config {
+    fee: u32,
+    paused: bool,
+}
+
 fn namespace_def() -> NamespaceDef {
     NamespaceDef {
         namespace: "dojo_core_test",
         resources: [
             TestResource::Model("Foo"), TestResource::Model("Foo2"), TestResource::Model("Foo3"),
-            TestResource::Model("Foo4"),
+            TestResource::Model("Foo4"), TestResource::Model("Narrowed"),
+            TestResource::Model("Config"),
         ]
             .span(),
     }
@@ -87,6 +136,22 @@ fn test_model_definition() {
     assert_eq!(definition.unpacked_size, dojo::meta::introspect::Introspect::<Foo>::size());
 }
 
+#[test]
+fn test_model_default_values() {
+    let definition = dojo::model::Model::<WithDefaults>::definition();
+    assert_eq!(definition.default_values, [10, 0].span());
+
+    let hp = *definition.schema.children[1];
+    assert_eq!(hp.attr_names(), [].span());
+    assert_eq!(hp.default_value(), Option::Some([10].span()));
+
+    let level = *definition.schema.children[2];
+    assert_eq!(level.default_value(), Option::None);
+
+    let value: WithDefaultsValue = Default::default();
+    assert_eq!(value.hp, 10);
+}
+
 #[test]
 fn test_values() {
     let mvalues = FooValue { v1: 3, v2: 4 };
@@ -338,3 +403,58 @@ fn test_read_schemas() {
             && schema_2.v3.d == foo_2.v3.d,
     );
 }
+
+#[test]
+fn test_singleton_model() {
+    let mut world = spawn_foo_world();
+
+    let config = Config { fee: 5, paused: true };
+    assert_eq!(config.entity_id(), dojo::utils::singleton_entity_id());
+
+    world.write_model(@config);
+
+    let read: Config = world.read_model(());
+    assert_eq!(read, config);
+
+    let value: ConfigValue = world.read_value(());
+    assert!(value.fee == config.fee && value.paused == config.paused);
+
+    // the key type of a singleton model is `()`.
+    let keys: () = config.keys();
+    let ptr = Model::<Config>::ptr_from_keys(keys);
+    assert_eq!(ptr.id, dojo::utils::singleton_entity_id());
+
+    world.write_member(ptr, selector!("fee"), 8_u32);
+    let fee: u32 = world.read_member(ptr, selector!("fee"));
+    assert_eq!(fee, 8);
+
+    world.erase_model(@config);
+
+    let read: Config = world.read_model(());
+    assert_eq!(read, Config { fee: 0, paused: false });
+}
+
+#[test]
+fn test_skipped_and_narrowed_members() {
+    let mut world = spawn_foo_world();
+
+    assert_eq!(
+        dojo::model::Model::<Narrowed>::layout(),
+        dojo::meta::Layout::Fixed([8, 16, 2].span()),
+    );
+
+    let narrowed = Narrowed { id: 1, level: 42, health: 100, stance: Stance::Dodge, cached: 12 };
+    world.write_model(@narrowed);
+
+    let read: Narrowed = world.read_model(narrowed.id);
+    assert_eq!(read.level, 42);
+    assert_eq!(read.health, 100);
+    assert_eq!(read.stance, Stance::Dodge);
+    assert_eq!(read.cached, 0);
+}
+
+#[test]
+#[should_panic(expected: "The variant selectors of the enum don't fit in 1 bits.")]
+fn test_narrowed_enum_with_too_many_variants() {
+    dojo::model::Model::<TooNarrow>::layout();
+}
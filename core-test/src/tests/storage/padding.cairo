@@ -0,0 +1,113 @@
+use dojo::model::{Model, ModelStorage};
+use dojo::model::model::ModelParser;
+use dojo::model::model_value::ModelValueParser;
+use dojo::storage::padding::{deserialize_padded, serialize_padded};
+use crate::world::{NamespaceDef, TestResource, spawn_test_world};
+
+#[derive(IntrospectPacked, Copy, Drop, Serde, Debug, PartialEq)]
+enum State {
+    Idle,
+    Moving: (u32, u32),
+    Attacking: u64,
+}
+
+#[derive(Copy, Drop, Serde, Debug, PartialEq)]
+#[dojo::model]
+struct Fighter {
+    #[key]
+    id: felt252,
+    state: State,
+    level: u8,
+}
+
+#[test]
+fn test_serde_is_not_padded() {
+    let mut serialized = array![];
+    Serde::<State>::serialize(@State::Attacking(7), ref serialized);
+
+    assert_eq!(serialized.span(), [2, 7].span());
+}
+
+#[test]
+fn test_serialize_padded() {
+    let mut serialized = array![];
+    serialize_padded(@State::Idle, ref serialized);
+    serialize_padded(@State::Moving((1, 2)), ref serialized);
+    serialize_padded(@State::Attacking(7), ref serialized);
+
+    assert_eq!(serialized.span(), [0, 0, 0, 1, 1, 2, 2, 7, 0].span());
+}
+
+#[test]
+fn test_deserialize_padded() {
+    let mut serialized = [0, 0, 0, 1, 1, 2, 2, 7, 0].span();
+
+    assert_eq!(deserialize_padded::<State>(ref serialized), Option::Some(State::Idle));
+    assert_eq!(deserialize_padded::<State>(ref serialized), Option::Some(State::Moving((1, 2))));
+    assert_eq!(deserialize_padded::<State>(ref serialized), Option::Some(State::Attacking(7)));
+    assert!(serialized.is_empty());
+}
+
+#[test]
+fn test_deserialize_padded_without_padding() {
+    let mut serialized = [7, 1, 2, 3].span();
+
+    assert_eq!(deserialize_padded::<u8>(ref serialized), Option::Some(7));
+    assert_eq!(deserialize_padded::<u256>(ref serialized), Option::Some(u256 { low: 1, high: 2 }));
+    assert_eq!(serialized, [3].span());
+}
+
+#[test]
+fn test_deserialize_padded_missing_values() {
+    let mut serialized = [2, 7].span();
+
+    assert_eq!(deserialize_padded::<State>(ref serialized), Option::None);
+}
+
+#[test]
+fn test_model_values_are_padded() {
+    let fighter = Fighter { id: 1, state: State::Attacking(7), level: 3 };
+
+    let values = ModelParser::<Fighter>::serialize_values(@fighter);
+    assert_eq!(values, [2, 7, 0, 3].span());
+
+    let value = FighterValue { state: State::Attacking(7), level: 3 };
+    assert_eq!(ModelValueParser::<FighterValue>::serialize_values(@value), values);
+
+    let mut keys = ModelParser::<Fighter>::serialize_keys(@fighter);
+    let mut model_values = values;
+    assert_eq!(
+        ModelParser::<Fighter>::deserialize(ref keys, ref model_values), Option::Some(fighter),
+    );
+
+    let mut values = values;
+    assert_eq!(ModelValueParser::<FighterValue>::deserialize(ref values), Option::Some(value));
+}
+
+#[test]
+fn test_member_is_padded_in_storage() {
+    let mut world = spawn_test_world(
+        [
+            NamespaceDef {
+                namespace: "dojo_core_test", resources: [TestResource::Model("Fighter")].span(),
+            },
+        ]
+            .span(),
+    );
+
+    let fighter = Fighter { id: 1, state: State::Moving((1, 2)), level: 3 };
+    world.write_model(@fighter);
+
+    // the smaller variant is padded up to the fixed layout of the member.
+    world.write_member(fighter.ptr(), selector!("state"), State::Attacking(7));
+    let state: State = world.read_member(fighter.ptr(), selector!("state"));
+    assert_eq!(state, State::Attacking(7));
+
+    let read: Fighter = world.read_model(1);
+    assert_eq!(read, Fighter { id: 1, state: State::Attacking(7), level: 3 });
+
+    world.write_member(fighter.ptr(), selector!("state"), State::Idle);
+    let states: Array<State> = world
+        .read_member_of_models([fighter.ptr()].span(), selector!("state"));
+    assert_eq!(states, array![State::Idle]);
+}
@@ -33,7 +33,7 @@ pub mod meta {
     };
 
     pub mod introspect;
-    pub use introspect::{Introspect, Ty, TyCompareTrait};
+    pub use introspect::{Introspect, MemberTrait, Ty, TyCompareTrait};
 
     pub mod layout;
     pub use layout::{Layout, FieldLayout, LayoutCompareTrait};
@@ -64,6 +64,7 @@ pub mod model {
 pub mod storage {
     pub mod database;
     pub mod packing;
+    pub mod padding;
     pub mod layout;
     pub mod storage;
     pub mod entity_model;
@@ -76,7 +77,9 @@ pub mod utils {
     };
 
     pub mod key;
-    pub use key::{entity_id_from_serialized_keys, combine_key, entity_id_from_keys};
+    pub use key::{
+        entity_id_from_serialized_keys, combine_key, entity_id_from_keys, singleton_entity_id,
+    };
 
     pub mod layout;
     pub use layout::{find_field_layout, find_model_field_layout};
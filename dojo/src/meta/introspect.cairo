@@ -200,6 +200,40 @@ pub struct Member {
     pub ty: Ty,
 }
 
+/// Access to the attributes of a member. A member with a declared default value
+/// has the 'default' attribute as last attribute, followed by the length of the
+/// serialized default value and the serialized default value itself.
+#[generate_trait]
+pub impl MemberImpl of MemberTrait {
+    /// Returns the attribute names of the member, without the default value.
+    fn attr_names(self: @Member) -> Span<felt252> {
+        let attrs = *self.attrs;
+        let mut i = 0;
+
+        while i < attrs.len() {
+            if *attrs[i] == 'default' {
+                break;
+            }
+            i += 1;
+        };
+
+        attrs.slice(0, i)
+    }
+
+    /// Returns the serialized default value of the member, if any.
+    fn default_value(self: @Member) -> Option<Span<felt252>> {
+        let attrs = *self.attrs;
+        let names_len = self.attr_names().len();
+
+        if names_len + 2 > attrs.len() {
+            return Option::None;
+        }
+
+        let len: u32 = (*attrs[names_len + 1]).try_into().unwrap();
+        Option::Some(attrs.slice(names_len + 2, len))
+    }
+}
+
 pub trait TyCompareTrait<T> {
     fn is_an_upgrade_of(self: @T, old: @T) -> bool;
 }
@@ -312,17 +346,20 @@ impl StructCompareImpl of TyCompareTrait<Struct> {
 
 impl MemberCompareImpl of TyCompareTrait<Member> {
     fn is_an_upgrade_of(self: @Member, old: @Member) -> bool {
-        if self.name != old.name || self.attrs != old.attrs {
+        // default values are not stored, so they can be updated.
+        let attrs = self.attr_names();
+
+        if self.name != old.name || attrs != old.attr_names() {
             return false;
         }
 
         let mut i = 0;
         let is_key = loop {
-            if i >= (*self).attrs.len() {
+            if i >= attrs.len() {
                 break false;
             }
 
-            if *self.attrs[i] == 'key' {
+            if *attrs[i] == 'key' {
                 break true;
             }
 
@@ -1,4 +1,5 @@
-use dojo::storage::packing::calculate_packed_size;
+use dojo::meta::{Introspect, Ty};
+use dojo::storage::packing::{calculate_packed_size, pow2_const};
 
 #[derive(Copy, Drop, Serde, Debug, PartialEq)]
 pub struct FieldLayout {
@@ -48,3 +49,30 @@ pub fn compute_packed_size(layout: Layout) -> Option<usize> {
         Option::None
     }
 }
+
+/// Returns the packed layout of an enum whose variant selector is narrowed to `bits`,
+/// as set with `#[bits(N)]` on a member of a packed struct.
+/// Panics if `T` is not an enum, or if its variant selectors don't fit in `bits`.
+pub fn narrow_enum_layout<T, +Introspect<T>>(bits: u8) -> Layout {
+    let variants_count = match Introspect::<T>::ty() {
+        Ty::Enum(e) => e.children.len(),
+        _ => panic!("The bits attribute can only be used on unsigned integers and enums."),
+    };
+
+    // the last variant selector is `variants_count - 1`.
+    let variants_count: u256 = variants_count.into();
+    assert!(
+        variants_count <= pow2_const(bits),
+        "The variant selectors of the enum don't fit in {} bits.",
+        bits,
+    );
+
+    match Introspect::<T>::layout() {
+        Layout::Fixed(widths) => {
+            let mut narrowed = array![bits];
+            narrowed.append_span(widths.slice(1, widths.len() - 1));
+            Layout::Fixed(narrowed.span())
+        },
+        _ => panic!("A packed model layout must contain Fixed layouts only."),
+    }
+}
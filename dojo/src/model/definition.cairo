@@ -22,6 +22,15 @@ pub trait ModelDefinition<T> {
     fn layout() -> Layout;
     fn schema() -> Struct;
     fn size() -> Option<usize>;
+    /// The serialized values of the model value built with the default values
+    /// declared with `#[default(...)]`, empty if no default value is declared.
+    ///
+    /// The default values are not applied when reading an entity which has never been
+    /// written, as the world storage can't tell it apart from an entity written with
+    /// zero values: use `Default::default()` to build a model with its default values.
+    fn default_values() -> Span<felt252> {
+        [].span()
+    }
 }
 
 /// A plain struct with all the fields of a model definition.
@@ -32,4 +41,5 @@ pub struct ModelDef {
     pub schema: Struct,
     pub packed_size: Option<usize>,
     pub unpacked_size: Option<usize>,
+    pub default_values: Span<felt252>,
 }
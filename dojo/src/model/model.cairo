@@ -52,8 +52,10 @@ pub trait KeyParser<M, K> {
 pub trait ModelParser<M> {
     /// Serializes the keys of the model.
     fn serialize_keys(self: @M) -> Span<felt252>;
-    /// Serializes the values of the model.
+    /// Serializes the values of the model, as stored in the world storage.
     fn serialize_values(self: @M) -> Span<felt252>;
+    /// Deserializes the model from its serialized keys and values.
+    fn deserialize(ref keys: Span<felt252>, ref values: Span<felt252>) -> Option<M>;
 }
 
 /// The `Model` trait.
@@ -122,12 +124,8 @@ pub impl ModelImpl<M, +ModelParser<M>, +ModelDefinition<M>, +Serde<M>, +Drop<M>>
         ModelParser::<M>::serialize_values(self)
     }
 
-    fn from_serialized(keys: Span<felt252>, values: Span<felt252>) -> Option<M> {
-        let mut serialized: Array<felt252> = keys.into();
-        serialized.append_span(values);
-        let mut span = serialized.span();
-
-        Serde::<M>::deserialize(ref span)
+    fn from_serialized(mut keys: Span<felt252>, mut values: Span<felt252>) -> Option<M> {
+        ModelParser::<M>::deserialize(ref keys, ref values)
     }
 
     fn name() -> ByteArray {
@@ -169,6 +167,7 @@ pub impl ModelImpl<M, +ModelParser<M>, +ModelDefinition<M>, +Serde<M>, +Drop<M>>
             schema: Self::schema(),
             packed_size: Self::packed_size(),
             unpacked_size: Self::unpacked_size(),
+            default_values: ModelDefinition::<M>::default_values(),
         }
     }
 
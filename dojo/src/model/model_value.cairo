@@ -6,6 +6,8 @@ pub trait ModelValueKey<V, K> {}
 pub trait ModelValueParser<V> {
     /// Serializes the values of the model and returns them as a `Span<felt252>`.
     fn serialize_values(self: @V) -> Span<felt252>;
+    /// Deserializes the model value from its serialized values.
+    fn deserialize(ref values: Span<felt252>) -> Option<V>;
 }
 
 /// The `ModelValue` trait defines a set of methods that must be implemented by any model value type
@@ -32,7 +34,7 @@ pub impl ModelValueImpl<V, +Serde<V>, +ModelDefinition<V>, +ModelValueParser<V>>
     }
 
     fn from_serialized(mut values: Span<felt252>) -> Option<V> {
-        Serde::<V>::deserialize(ref values)
+        ModelValueParser::<V>::deserialize(ref values)
     }
 
     fn name() -> ByteArray {
@@ -14,6 +14,8 @@ pub trait ModelStorage<S, M> {
     fn write_models(ref self: S, models: Span<@M>);
 
     /// Retrieves a model of type `M` using the provided key of type `K`.
+    /// The values of an entity which has never been written are zero, even for the
+    /// members declared with `#[default(...)]`.
     fn read_model<K, +Drop<K>, +Serde<K>>(self: @S, keys: K) -> M;
 
     /// Retrieves multiple models of type `M` using the provided keys of type `K`.
@@ -36,6 +38,9 @@ pub trait ModelStorage<S, M> {
     fn erase_models_ptrs(ref self: S, ptrs: Span<ModelPtr<M>>);
 
     /// Retrieves a model of type `M` using the provided entity id.
+    ///
+    /// The padding of the packed enums with variants of different sizes is removed
+    /// before deserializing the member (see `dojo::storage::padding`).
     fn read_member<T, +Serde<T>>(self: @S, ptr: ModelPtr<M>, field_selector: felt252) -> T;
 
     /// Retrieves a single member from multiple models.
@@ -44,6 +49,9 @@ pub trait ModelStorage<S, M> {
     ) -> Array<T>;
 
     /// Updates a member of a model.
+    ///
+    /// The data of the packed enums with variants of different sizes are padded
+    /// as for the whole model (see `dojo::storage::padding`).
     fn write_member<T, +Serde<T>, +Drop<T>>(
         ref self: S, ptr: ModelPtr<M>, field_selector: felt252, value: T,
     );
@@ -68,6 +76,7 @@ pub trait ModelStorage<S, M> {
 /// A `ModelValueStorage` trait that abstracts where the storage is.
 pub trait ModelValueStorage<S, V> {
     /// Retrieves a model value of type `V` using the provided key of type `K`.
+    /// As for `read_model`, the default values of the members are not applied.
     fn read_value<K, +Drop<K>, +Serde<K>, +ModelValueKey<V, K>>(self: @S, keys: K) -> V;
 
     /// Retrieves multiple model values of type `V` using the provided keys of type `K`.
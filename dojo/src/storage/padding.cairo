@@ -0,0 +1,308 @@
+//! Padding of packed enums in the world storage.
+//!
+//! The fixed layout of a packed enum with variants of different sizes is the variant
+//! selector followed by the data of its largest variant. The `Serde` implementation of
+//! such an enum is left untouched: the serialized data of the smaller variants are only
+//! padded with zeros when they are written to the world storage, and the padding is
+//! removed when they are read back.
+
+use dojo::meta::{Introspect, Layout, Ty};
+use dojo::meta::introspect::{Member, Struct};
+use dojo::utils::find_field_layout;
+
+/// Serializes a value to be written to the world storage, padding the data of
+/// the packed enums it contains up to the size of their largest variant.
+pub fn serialize_padded<T, +Serde<T>, +Introspect<T>>(value: @T, ref serialized: Array<felt252>) {
+    let mut data = array![];
+    Serde::<T>::serialize(value, ref data);
+
+    // a fixed size type serialized with its full size does not contain any padded enum.
+    if let Option::Some(size) = Introspect::<T>::size() {
+        if data.len() == size {
+            serialized.append_span(data.span());
+            return;
+        }
+    }
+
+    let mut data = data.span();
+    pad_layout(@Introspect::<T>::ty(), @Introspect::<T>::layout(), true, ref data, ref serialized)
+        .expect('invalid serialized data');
+}
+
+/// Deserializes a value read from the world storage, removing the padding of
+/// the packed enums it contains.
+pub fn deserialize_padded<T, +Serde<T>, +Introspect<T>, +Drop<T>>(
+    ref serialized: Span<felt252>,
+) -> Option<T> {
+    // a fixed size value is stored with its full size. If its data are deserialized
+    // and serialized back with `Serde` to the same values, they don't contain any padding.
+    if let Option::Some(size) = Introspect::<T>::size() {
+        if serialized.len() >= size {
+            let stored = serialized.slice(0, size);
+            let mut data = stored;
+
+            if let Option::Some(value) = Serde::<T>::deserialize(ref data) {
+                let mut reserialized = array![];
+                Serde::<T>::serialize(@value, ref reserialized);
+
+                if reserialized.span() == stored {
+                    serialized = serialized.slice(size, serialized.len() - size);
+                    return Option::Some(value);
+                }
+            }
+        }
+    }
+
+    let mut data = array![];
+    pad_layout(
+        @Introspect::<T>::ty(), @Introspect::<T>::layout(), false, ref serialized, ref data,
+    )?;
+
+    let mut data = data.span();
+    Serde::<T>::deserialize(ref data)
+}
+
+/// Pads (`padding` is true) or unpads the serialized value of a model member, identified
+/// by its selector, as it is done for the whole model.
+///
+/// The type of the member is found in the model schema, whose value members are in
+/// the same order as the fields of the model layout.
+pub fn pad_member(
+    schema: @Struct, layout: @Layout, field_selector: felt252, padding: bool, values: Span<felt252>,
+) -> Span<felt252> {
+    let mut fields = match layout {
+        Layout::Struct(fields) => *fields,
+        _ => { return values; },
+    };
+
+    for member in *schema.children {
+        if !is_key(member) {
+            let field = fields.pop_front().expect('invalid model layout');
+            if *field.selector == field_selector {
+                // a value written with its full fixed size does not contain any padded enum.
+                if let Layout::Fixed(sizes) = field.layout {
+                    if padding && values.len() == (*sizes).len() {
+                        return values;
+                    }
+                }
+
+                let mut input = values;
+                let mut output = array![];
+                pad_layout(member.ty, field.layout, padding, ref input, ref output)
+                    .expect('invalid serialized data');
+                return output.span();
+            }
+        }
+    };
+
+    values
+}
+
+/// Copies the values of a type from `input` to `output`, adding (`padding` is true) or
+/// removing the padding of the enums with a fixed layout.
+fn pad_layout(
+    ty: @Ty, layout: @Layout, padding: bool, ref input: Span<felt252>, ref output: Array<felt252>,
+) -> Option<()> {
+    match (layout, ty) {
+        (Layout::Fixed(_), _) => pad_fixed(ty, padding, ref input, ref output),
+        (
+            Layout::Struct(fields), Ty::Struct(s),
+        ) => {
+            // key members are part of the schema but not of the layout.
+            let mut fields = *fields;
+            for member in *s.children {
+                if is_key(member) {
+                    copy_ty(member.ty, ref input, ref output)?;
+                } else {
+                    let field = fields.pop_front()?;
+                    pad_layout(member.ty, field.layout, padding, ref input, ref output)?;
+                }
+            };
+            Option::Some(())
+        },
+        (
+            Layout::Tuple(layouts), Ty::Tuple(tys),
+        ) => {
+            let mut layouts = *layouts;
+            for item_ty in *tys {
+                pad_layout(item_ty, layouts.pop_front()?, padding, ref input, ref output)?;
+            };
+            Option::Some(())
+        },
+        (
+            Layout::Array(item_layout), Ty::Array(item_ty),
+        ) => {
+            let len = *input.pop_front()?;
+            output.append(len);
+
+            let item_layout = (*item_layout).at(0);
+            let item_ty = (*item_ty).at(0);
+            let mut i: u32 = 0;
+            let len: u32 = len.try_into()?;
+            while i < len {
+                pad_layout(item_ty, item_layout, padding, ref input, ref output)?;
+                i += 1;
+            };
+            Option::Some(())
+        },
+        (
+            Layout::Enum(variants), Ty::Enum(e),
+        ) => {
+            let selector = *input.pop_front()?;
+            output.append(selector);
+
+            let variant_layout = find_field_layout(selector, *variants)?;
+            let (_, variant_ty) = *(*e.children).get(selector.try_into()?)?.unbox();
+            pad_layout(@variant_ty, @variant_layout, padding, ref input, ref output)
+        },
+        _ => copy_ty(ty, ref input, ref output),
+    }
+}
+
+/// Copies the values of a type with a fixed layout, where every enum is padded.
+fn pad_fixed(
+    ty: @Ty, padding: bool, ref input: Span<felt252>, ref output: Array<felt252>,
+) -> Option<()> {
+    match ty {
+        Ty::Struct(s) => {
+            for member in *s.children {
+                pad_fixed(member.ty, padding, ref input, ref output)?;
+            };
+            Option::Some(())
+        },
+        Ty::Tuple(tys) => {
+            for item_ty in *tys {
+                pad_fixed(item_ty, padding, ref input, ref output)?;
+            };
+            Option::Some(())
+        },
+        Ty::Enum(e) => {
+            let selector = *input.pop_front()?;
+            output.append(selector);
+
+            let (_, variant_ty) = *(*e.children).get(selector.try_into()?)?.unbox();
+            pad_fixed(@variant_ty, padding, ref input, ref output)?;
+
+            let mut padding_size = padded_size(ty) - 1 - padded_size(@variant_ty);
+            while padding_size > 0 {
+                if padding {
+                    output.append(0);
+                } else {
+                    input.pop_front()?;
+                }
+                padding_size -= 1;
+            };
+            Option::Some(())
+        },
+        _ => copy_ty(ty, ref input, ref output),
+    }
+}
+
+/// Copies the values of a type, without any padding.
+fn copy_ty(ty: @Ty, ref input: Span<felt252>, ref output: Array<felt252>) -> Option<()> {
+    match ty {
+        Ty::Primitive(name) => copy_values(primitive_size(*name), ref input, ref output),
+        Ty::Struct(s) => {
+            for member in *s.children {
+                copy_ty(member.ty, ref input, ref output)?;
+            };
+            Option::Some(())
+        },
+        Ty::Tuple(tys) => {
+            for item_ty in *tys {
+                copy_ty(item_ty, ref input, ref output)?;
+            };
+            Option::Some(())
+        },
+        Ty::Enum(e) => {
+            let selector = *input.pop_front()?;
+            output.append(selector);
+
+            let (_, variant_ty) = *(*e.children).get(selector.try_into()?)?.unbox();
+            copy_ty(@variant_ty, ref input, ref output)
+        },
+        Ty::Array(item_ty) => {
+            let len = *input.pop_front()?;
+            output.append(len);
+
+            let item_ty = (*item_ty).at(0);
+            let len: u32 = len.try_into()?;
+            let mut i: u32 = 0;
+            while i < len {
+                copy_ty(item_ty, ref input, ref output)?;
+                i += 1;
+            };
+            Option::Some(())
+        },
+        Ty::ByteArray => {
+            // the full words, followed by the pending word and its length.
+            let len = *input.pop_front()?;
+            output.append(len);
+
+            let len: u32 = len.try_into()?;
+            copy_values(len + 2, ref input, ref output)
+        },
+    }
+}
+
+fn copy_values(len: u32, ref input: Span<felt252>, ref output: Array<felt252>) -> Option<()> {
+    if input.len() < len {
+        return Option::None;
+    }
+
+    output.append_span(input.slice(0, len));
+    input = input.slice(len, input.len() - len);
+    Option::Some(())
+}
+
+/// The number of values of a type with a fixed layout, where every enum is padded
+/// up to the size of its largest variant.
+fn padded_size(ty: @Ty) -> u32 {
+    match ty {
+        Ty::Primitive(name) => primitive_size(*name),
+        Ty::Struct(s) => {
+            let mut size = 0;
+            for member in *s.children {
+                size += padded_size(member.ty);
+            };
+            size
+        },
+        Ty::Tuple(tys) => {
+            let mut size = 0;
+            for item_ty in *tys {
+                size += padded_size(item_ty);
+            };
+            size
+        },
+        Ty::Enum(e) => {
+            let mut max_size = 0;
+            for variant in *e.children {
+                let (_, variant_ty) = *variant;
+                let size = padded_size(@variant_ty);
+                if size > max_size {
+                    max_size = size;
+                }
+            };
+            // one value for the variant selector.
+            1 + max_size
+        },
+        // types without a fixed size cannot be part of a fixed layout.
+        _ => 0,
+    }
+}
+
+fn primitive_size(name: felt252) -> u32 {
+    if name == 'u256' {
+        2
+    } else {
+        1
+    }
+}
+
+/// The `key` attribute is always the first attribute of a member.
+fn is_key(member: @Member) -> bool {
+    match (*member.attrs).get(0) {
+        Option::Some(attr) => *attr.unbox() == 'key',
+        Option::None => false,
+    }
+}
@@ -13,6 +13,11 @@ pub fn entity_id_from_serialized_keys(keys: Span<felt252>) -> felt252 {
     core::poseidon::poseidon_hash_span(keys)
 }
 
+/// Computes the fixed entity id of singleton models (models without keys).
+pub fn singleton_entity_id() -> felt252 {
+    entity_id_from_serialized_keys([].span())
+}
+
 /// Combine parent and child keys to build one full key.
 pub fn combine_key(parent_key: felt252, child_key: felt252) -> felt252 {
     core::poseidon::poseidon_hash_span([parent_key, child_key].span())
@@ -7,6 +7,7 @@ use dojo::model::{
 };
 use dojo::event::{Event, EventStorage};
 use dojo::meta::{Layout, FieldLayout, Introspect};
+use dojo::storage::padding::pad_member;
 use dojo::utils::{
     entity_id_from_keys, entity_id_from_serialized_keys, serialize_inline, find_model_field_layout,
     deserialize_unwrap,
@@ -229,12 +230,16 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     fn read_member<T, +Serde<T>>(
         self: @WorldStorage, ptr: ModelPtr<M>, field_selector: felt252,
     ) -> T {
+        let field_layout = field_layout_unwrap::<M>(field_selector);
+        let values = IWorldDispatcherTrait::entity(
+            *self.dispatcher,
+            Model::<M>::selector(*self.namespace_hash),
+            ModelIndex::MemberId((ptr.id, field_selector)),
+            field_layout,
+        );
         deserialize_unwrap(
-            IWorldDispatcherTrait::entity(
-                *self.dispatcher,
-                Model::<M>::selector(*self.namespace_hash),
-                ModelIndex::MemberId((ptr.id, field_selector)),
-                field_layout_unwrap::<M>(field_selector),
+            pad_member(
+                @Model::<M>::schema(), @Model::<M>::layout(), field_selector, false, values,
             ),
         )
     }
@@ -242,6 +247,8 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     fn read_member_of_models<T, +Serde<T>, +Drop<T>>(
         self: @WorldStorage, ptrs: Span<ModelPtr<M>>, field_selector: felt252,
     ) -> Array<T> {
+        let schema = Model::<M>::schema();
+        let layout = Model::<M>::layout();
         let mut values: Array<T> = array![];
         for entity in IWorldDispatcherTrait::entities(
             *self.dispatcher,
@@ -249,7 +256,8 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
             ptrs.to_member_indexes(field_selector),
             field_layout_unwrap::<M>(field_selector),
         ) {
-            values.append(deserialize_unwrap(*entity));
+            let entity = pad_member(@schema, @layout, field_selector, false, *entity);
+            values.append(deserialize_unwrap(entity));
         };
         values
     }
@@ -261,7 +269,13 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
             self.dispatcher,
             Model::<M>::selector(self.namespace_hash),
             ModelIndex::MemberId((ptr.id, field_selector)),
-            serialize_inline(@value),
+            pad_member(
+                @Model::<M>::schema(),
+                @Model::<M>::layout(),
+                field_selector,
+                true,
+                serialize_inline(@value),
+            ),
             field_layout_unwrap::<M>(field_selector),
         );
     }
@@ -269,9 +283,12 @@ pub impl ModelStorageWorldStorageImpl<M, +Model<M>, +Drop<M>> of ModelStorage<Wo
     fn write_member_of_models<T, +Serde<T>, +Drop<T>>(
         ref self: WorldStorage, ptrs: Span<ModelPtr<M>>, field_selector: felt252, values: Span<T>,
     ) {
+        let schema = Model::<M>::schema();
+        let layout = Model::<M>::layout();
         let mut serialized_values = ArrayTrait::<Span<felt252>>::new();
         for value in values {
-            serialized_values.append(serialize_inline(value));
+            serialized_values
+                .append(pad_member(@schema, @layout, field_selector, true, serialize_inline(value)));
         };
         IWorldDispatcherTrait::set_entities(
             self.dispatcher,
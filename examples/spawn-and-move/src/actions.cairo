@@ -227,13 +227,11 @@ pub mod actions {
         }
 
         /// Example of how to get a dispatcher from a declared dojo library.
-        /// `{library_module_name}_v{version}`.
+        /// The library is resolved from the world as `{library_module_name}_v{version}`.
         fn simple_math_dispatcher(
             self: @ContractState, world: @WorldStorage,
         ) -> SimpleMathLibraryDispatcher {
-            let (_, class_hash) = world.dns(@"simple_math_v0_1_0").expect('simple_math not found');
-
-            SimpleMathLibraryDispatcher { class_hash }
+            dojo_examples::lib_math::simple_math::simple_math_dispatcher(world)
         }
     }
 }
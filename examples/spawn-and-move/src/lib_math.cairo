@@ -4,7 +4,7 @@ pub trait SimpleMath<T> {
     fn decrement_saturating(self: @T, value: u8) -> u8;
 }
 
-#[dojo::library]
+#[dojo::library(version: "0.1.0")]
 pub mod simple_math {
     use super::SimpleMath;
     use core::num::traits::SaturatingSub;
@@ -0,0 +1,189 @@
+use cairo_lang_macro::{quote, Diagnostic, ProcMacroResult, TokenStream};
+use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::node::with_db::SyntaxNodeWithDb;
+use cairo_lang_syntax::node::Terminal;
+use cairo_lang_syntax::node::{
+    ast::{self, MaybeModuleBody},
+    TypedSyntaxNode,
+};
+
+use crate::constants::{CONSTRUCTOR_FN, DOJO_INIT_FN};
+use crate::helpers::{DiagnosticsExt, DojoChecker, DojoParser, DojoTokenizer, ProcMacroResultExt};
+
+#[derive(Debug)]
+pub struct DojoComponent {
+    diagnostics: Vec<Diagnostic>,
+    has_event: bool,
+    has_storage: bool,
+}
+
+impl DojoComponent {
+    pub fn new() -> Self {
+        Self {
+            diagnostics: vec![],
+            has_event: false,
+            has_storage: false,
+        }
+    }
+
+    pub fn process(token_stream: TokenStream) -> ProcMacroResult {
+        let db = SimpleParserDatabase::default();
+
+        if let Some(module_ast) = DojoParser::parse_and_find_module(&db, &token_stream) {
+            return DojoComponent::process_ast(&db, &module_ast);
+        }
+
+        ProcMacroResult::fail("'dojo::component' must be used on module only.".to_string())
+    }
+
+    fn process_ast(db: &SimpleParserDatabase, module_ast: &ast::ItemModule) -> ProcMacroResult {
+        let mut component = DojoComponent::new();
+
+        let name = module_ast.name(db).text(db).to_string();
+
+        if let Some(failure) = DojoChecker::is_name_valid("component", &name) {
+            return failure;
+        }
+
+        if let MaybeModuleBody::Some(body) = module_ast.body(db) {
+            let mut body_nodes: Vec<_> = body
+                .items(db)
+                .elements(db)
+                .iter()
+                .map(|el| {
+                    match el {
+                        ast::ModuleItem::Enum(ref enum_ast) => {
+                            if enum_ast.name(db).text(db) == "Event" {
+                                component.has_event = true;
+                            }
+                        }
+                        ast::ModuleItem::Struct(ref struct_ast) => {
+                            if struct_ast.name(db).text(db) == "Storage" {
+                                component.has_storage = true;
+                            }
+                        }
+                        ast::ModuleItem::FreeFunction(ref fn_ast) => {
+                            let fn_name = fn_ast.declaration(db).name(db).text(db);
+
+                            if fn_name == CONSTRUCTOR_FN || fn_name == DOJO_INIT_FN {
+                                component.diagnostics.push_error(format!(
+                                    "The component '{name}' cannot have a {fn_name} function. \
+                                    Expose an initializer function instead, to be called by \
+                                    the embedding contract."
+                                ));
+                            }
+                        }
+                        _ => {}
+                    };
+
+                    let el = el.as_syntax_node();
+                    let el = SyntaxNodeWithDb::new(&el, db);
+                    quote! { #el }
+                })
+                .collect::<Vec<TokenStream>>();
+
+            if !component.has_event {
+                body_nodes.push(component.create_event());
+            }
+
+            if !component.has_storage {
+                body_nodes.push(component.create_storage());
+            }
+
+            let component_code = DojoComponent::generate_component_code(&name, body_nodes);
+            return ProcMacroResult::finalize(component_code, component.diagnostics);
+        }
+
+        ProcMacroResult::fail(format!("The component '{name}' is empty."))
+    }
+
+    /// The component gets access to the world storage through the `IWorldProvider`
+    /// implementation of the embedding contract, which is provided by `dojo::contract`.
+    fn generate_component_code(name: &str, body: Vec<TokenStream>) -> TokenStream {
+        let name = DojoTokenizer::tokenize(name);
+
+        let mut content = TokenStream::new(vec![]);
+        content.extend(body);
+
+        quote! {
+            #[starknet::component]
+            pub mod #name {
+                #[generate_trait]
+                pub impl DojoComponentInternalImpl<
+                    TContractState,
+                    +HasComponent<TContractState>,
+                    +dojo::contract::components::world_provider::IWorldProvider<TContractState>,
+                > of DojoComponentInternalTrait<TContractState> {
+                    fn world(self: @ComponentState<TContractState>, namespace: @ByteArray) -> dojo::world::storage::WorldStorage {
+                        dojo::world::WorldStorageTrait::new(
+                            dojo::contract::components::world_provider::IWorldProvider::world_dispatcher(self.get_contract()),
+                            namespace
+                        )
+                    }
+
+                    fn world_ns_hash(self: @ComponentState<TContractState>, namespace_hash: felt252) -> dojo::world::storage::WorldStorage {
+                        dojo::world::WorldStorageTrait::new_from_hash(
+                            dojo::contract::components::world_provider::IWorldProvider::world_dispatcher(self.get_contract()),
+                            namespace_hash
+                        )
+                    }
+                }
+
+                #content
+            }
+        }
+    }
+
+    pub fn create_event(&mut self) -> TokenStream {
+        quote! {
+            #[event]
+            #[derive(Drop, starknet::Event)]
+            pub enum Event {}
+        }
+    }
+
+    pub fn create_storage(&mut self) -> TokenStream {
+        quote! {
+            #[storage]
+            pub struct Storage {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_with_init() {
+        let res = DojoComponent::process(TokenStream::new(vec![DojoTokenizer::tokenize(
+            "mod score_cpt {
+                fn dojo_init(self: @ContractState) {}
+            }",
+        )]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The component 'score_cpt' cannot have a dojo_init function. Expose an initializer \
+            function instead, to be called by the embedding contract."
+        );
+    }
+
+    #[test]
+    fn test_component_world_access() {
+        let res = DojoComponent::process(TokenStream::new(vec![DojoTokenizer::tokenize(
+            "mod score_cpt {
+                #[storage]
+                pub struct Storage {
+                    total: u32,
+                }
+            }",
+        )]));
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("DojoComponentInternalImpl"));
+        assert!(output.contains("total: u32"));
+    }
+}
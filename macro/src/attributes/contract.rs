@@ -1,5 +1,9 @@
-use cairo_lang_macro::{quote, Diagnostic, ProcMacroResult, TokenStream};
+use cairo_lang_macro::{quote, AuxData, Diagnostic, ProcMacroResult, TokenStream, TokenTree};
 use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::attribute::structured::{
+    AttributeArg, AttributeArgVariant, AttributeStructurize,
+};
+use cairo_lang_syntax::node::kind::SyntaxKind;
 use cairo_lang_syntax::node::with_db::SyntaxNodeWithDb;
 use cairo_lang_syntax::node::Terminal;
 use cairo_lang_syntax::node::{
@@ -7,8 +11,30 @@ use cairo_lang_syntax::node::{
     TypedSyntaxNode,
 };
 
-use crate::constants::{CONSTRUCTOR_FN, DOJO_INIT_FN};
-use crate::helpers::{DiagnosticsExt, DojoChecker, DojoParser, DojoTokenizer, ProcMacroResultExt};
+use std::str::FromStr;
+
+use starknet::core::utils::get_selector_from_name;
+
+use dojo_types::naming;
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Member, Struct, Ty};
+use dojo_types::system::{ContractManifest, Dependency};
+
+use crate::constants::{CONSTRUCTOR_FN, DOJO_INIT_FLAG_STORAGE, DOJO_INIT_FN};
+use crate::derives::introspect::utils::{
+    get_array_item_type, get_tuple_item_types, is_array, is_byte_array, is_tuple,
+};
+use crate::helpers::{
+    registry, DiagnosticsExt, DojoAnalyzer, DojoChecker, DojoParser, DojoTokenizer,
+    ProcMacroResultExt,
+};
+
+const EVENT_ITEM: &str = "Event";
+const WORLD_DEFAULT_FN: &str = "world_default";
+const STORAGE_ITEM: &str = "Storage";
+
+const DOJO_EVENT_VARIANTS: [&str; 2] = ["UpgradeableEvent", "WorldProviderEvent"];
+const DOJO_STORAGE_MEMBERS: [&str; 2] = ["upgradeable", "world_provider"];
 
 #[derive(Debug)]
 pub struct DojoContract {
@@ -17,6 +43,24 @@ pub struct DojoContract {
     has_storage: bool,
     has_init: bool,
     has_constructor: bool,
+    namespace: Option<String>,
+    dependencies: Vec<Dependency>,
+    detected_dependencies: Vec<Dependency>,
+    init_schema: Option<Ty>,
+    name: String,
+    constructor_args: bool,
+    owner_can_init: bool,
+    components: Vec<EmbeddedComponent>,
+    has_world_default: bool,
+}
+
+/// A component embedded in the contract with
+/// `component!(path: ..., storage: ..., event: ...)`.
+#[derive(Debug, Default)]
+struct EmbeddedComponent {
+    path: String,
+    storage: String,
+    event: String,
 }
 
 impl DojoContract {
@@ -27,20 +71,34 @@ impl DojoContract {
             has_storage: false,
             has_init: false,
             has_constructor: false,
+            namespace: None,
+            dependencies: vec![],
+            detected_dependencies: vec![],
+            init_schema: None,
+            name: String::default(),
+            constructor_args: false,
+            owner_can_init: false,
+            components: vec![],
+            has_world_default: false,
         }
     }
 
-    pub fn process(token_stream: TokenStream) -> ProcMacroResult {
+    pub fn process(args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
         let db = SimpleParserDatabase::default();
 
         if let Some(module_ast) = DojoParser::parse_and_find_module(&db, &token_stream) {
-            return DojoContract::process_ast(&db, &module_ast);
+            let args = DojoParser::parse_attribute_args(&db, &args);
+            return DojoContract::process_ast(&db, &module_ast, &args);
         }
 
         ProcMacroResult::fail("'dojo::contract' must be used on module only.".to_string())
     }
 
-    fn process_ast(db: &SimpleParserDatabase, module_ast: &ast::ItemModule) -> ProcMacroResult {
+    fn process_ast(
+        db: &SimpleParserDatabase,
+        module_ast: &ast::ItemModule,
+        args: &[AttributeArg],
+    ) -> ProcMacroResult {
         let mut contract = DojoContract::new();
 
         let name = module_ast.name(db).text(db).to_string();
@@ -49,7 +107,20 @@ impl DojoContract {
             return failure;
         }
 
+        contract.name = name.clone();
+
+        contract.parse_args(db, args);
+
+        match &contract.namespace {
+            Some(namespace) => registry::register_tag(namespace, &name),
+            None => registry::register_name(&name),
+        }
+
         if let MaybeModuleBody::Some(body) = module_ast.body(db) {
+            contract.analyze_dependencies(db, &body);
+            contract.collect_components(db, &body);
+            contract.has_world_default = defines_function(db, &body, WORLD_DEFAULT_FN);
+
             let mut body_nodes = body
                 .items(db)
                 .elements(db)
@@ -57,13 +128,34 @@ impl DojoContract {
                 .map(|el| {
                     match el {
                         ast::ModuleItem::Enum(ref enum_ast) => {
-                            if enum_ast.name(db).text(db).to_string() == "Event" {
-                                return contract.merge_event(db, enum_ast);
+                            match enum_ast.name(db).text(db).as_str() {
+                                EVENT_ITEM => return contract.merge_event(db, enum_ast),
+                                STORAGE_ITEM => contract.report_unmergeable_item(
+                                    STORAGE_ITEM,
+                                    "is declared as an enum",
+                                ),
+                                _ => {}
                             }
                         }
                         ast::ModuleItem::Struct(ref struct_ast) => {
-                            if struct_ast.name(db).text(db).to_string() == "Storage" {
-                                return contract.merge_storage(db, struct_ast);
+                            match struct_ast.name(db).text(db).as_str() {
+                                STORAGE_ITEM => return contract.merge_storage(db, struct_ast),
+                                EVENT_ITEM => contract
+                                    .report_unmergeable_item(EVENT_ITEM, "is declared as a struct"),
+                                _ => {}
+                            }
+                        }
+                        ast::ModuleItem::Use(ref use_ast) => {
+                            contract.check_use(db, use_ast);
+                        }
+                        ast::ModuleItem::TypeAlias(ref alias_ast) => {
+                            let alias_name = alias_ast.name(db).text(db);
+
+                            if alias_name == EVENT_ITEM || alias_name == STORAGE_ITEM {
+                                contract.report_unmergeable_item(
+                                    &alias_name,
+                                    "is declared as a type alias",
+                                );
                             }
                         }
                         ast::ModuleItem::FreeFunction(ref fn_ast) => {
@@ -91,6 +183,7 @@ impl DojoContract {
             }
 
             if !contract.has_init {
+                contract.init_schema = Some(build_init_schema(vec![]));
                 body_nodes.push(contract.create_init_fn());
             }
 
@@ -102,18 +195,258 @@ impl DojoContract {
                 body_nodes.push(contract.create_storage());
             }
 
-            let contract_code = DojoContract::generate_contract_code(&name, body_nodes);
-            return ProcMacroResult::finalize(contract_code, contract.diagnostics);
+            body_nodes.push(contract.create_dependencies_fn());
+
+            let manifest = contract.build_manifest(&name);
+            let contract_code = contract.generate_contract_code(&name, body_nodes);
+
+            let result = ProcMacroResult::finalize(contract_code, contract.diagnostics);
+
+            return match serde_json::to_vec(&manifest) {
+                Ok(manifest) => result.with_aux_data(AuxData::new(manifest)),
+                Err(_) => result,
+            };
         }
 
         ProcMacroResult::fail(format!("The contract '{name}' is empty."))
     }
 
-    fn generate_contract_code(name: &String, body: Vec<TokenStream>) -> TokenStream {
+    /// Parse the arguments of the contract attribute:
+    /// - `namespace`: the namespace the contract is deployed in,
+    /// - `reads`/`writes`: the models (names or tags) read/written by the contract.
+    fn parse_args(&mut self, db: &SimpleParserDatabase, args: &[AttributeArg]) {
+        let mut reads = vec![];
+        let mut writes = vec![];
+
+        for arg in args {
+            let AttributeArgVariant::Named { name, value } = &arg.variant else {
+                self.diagnostics.push_error(format!(
+                    "Unexpected argument '{}' for dojo::contract. Expected arguments: \
+                    namespace, reads, writes, constructor_args, owner_can_init.",
+                    arg.arg.as_syntax_node().get_text_without_trivia(db)
+                ));
+                continue;
+            };
+
+            match name.text.as_str() {
+                "namespace" => {
+                    if let ast::Expr::String(s) = value {
+                        let namespace = s.text(db).to_string().replace("\"", "");
+
+                        if naming::is_name_valid(&namespace) {
+                            self.namespace = Some(namespace);
+                        } else {
+                            self.diagnostics.push_error(format!(
+                                "The namespace '{namespace}' can only contain characters \
+                                (a-z/A-Z), digits (0-9) and underscore (_)."
+                            ));
+                        }
+                    } else {
+                        self.diagnostics
+                            .push_error("The contract namespace must be a string.".to_string());
+                    }
+                }
+                "reads" => reads = self.parse_model_list(db, "reads", value),
+                "writes" => writes = self.parse_model_list(db, "writes", value),
+                "constructor_args" => {
+                    self.constructor_args = self.parse_bool_arg(db, "constructor_args", value)
+                }
+                "owner_can_init" => {
+                    self.owner_can_init = self.parse_bool_arg(db, "owner_can_init", value)
+                }
+                other => self.diagnostics.push_error(format!(
+                    "Unexpected argument '{other}' for dojo::contract. Expected arguments: \
+                    namespace, reads, writes, constructor_args, owner_can_init."
+                )),
+            }
+        }
+
+        // the contract selector is required to check the ownership.
+        if self.owner_can_init && self.namespace.is_none() {
+            self.diagnostics.push_error(
+                "The 'owner_can_init' option requires the contract 'namespace' to be declared."
+                    .to_string(),
+            );
+        }
+
+        // model names are prefixed with the contract namespace, if declared.
+        let to_tag = |model: String| match &self.namespace {
+            Some(namespace) if !model.contains('-') => format!("{namespace}-{model}"),
+            _ => model,
+        };
+
+        for (model, is_write) in reads
+            .into_iter()
+            .map(|m| (m, false))
+            .chain(writes.into_iter().map(|m| (m, true)))
+        {
+            let model = to_tag(model);
+
+            match self.dependencies.iter_mut().find(|d| d.name == model) {
+                Some(dependency) => {
+                    dependency.read |= !is_write;
+                    dependency.write |= is_write;
+                }
+                None => self.dependencies.push(Dependency {
+                    name: model,
+                    read: !is_write,
+                    write: is_write,
+                }),
+            }
+        }
+    }
+
+    /// Parse a boolean contract option like `owner_can_init: true`.
+    fn parse_bool_arg(
+        &mut self,
+        db: &SimpleParserDatabase,
+        arg_name: &str,
+        value: &ast::Expr,
+    ) -> bool {
+        match value {
+            ast::Expr::True(_) => true,
+            ast::Expr::False(_) => false,
+            _ => {
+                self.diagnostics.push_error(format!(
+                    "The contract option '{arg_name}' must be a boolean (found: {}).",
+                    value.as_syntax_node().get_text_without_trivia(db)
+                ));
+                false
+            }
+        }
+    }
+
+    /// Parse a list of models like `[Position, "ns-Moves"]`, where each model
+    /// is identified by its name or by its tag.
+    fn parse_model_list(
+        &mut self,
+        db: &SimpleParserDatabase,
+        arg_name: &str,
+        value: &ast::Expr,
+    ) -> Vec<String> {
+        let ast::Expr::FixedSizeArray(array) = value else {
+            self.diagnostics.push_error(format!(
+                "The contract argument '{arg_name}' must be a list of model names or tags \
+                (i.e [Position, \"ns-Moves\"])."
+            ));
+            return vec![];
+        };
+
+        let mut models = vec![];
+
+        for expr in array.exprs(db).elements(db) {
+            let model = match &expr {
+                ast::Expr::Path(path) => path.as_syntax_node().get_text_without_trivia(db),
+                ast::Expr::String(s) => s.text(db).to_string().replace("\"", ""),
+                _ => expr.as_syntax_node().get_text_without_trivia(db),
+            };
+
+            let is_valid = if model.contains('-') {
+                naming::is_valid_tag(&model)
+            } else {
+                naming::is_name_valid(&model)
+            };
+
+            if is_valid {
+                models.push(model);
+            } else {
+                self.diagnostics.push_error(format!(
+                    "Invalid model '{model}' in '{arg_name}'. A model must be identified by \
+                    its name or by its tag (`namespace-name`)."
+                ));
+            }
+        }
+
+        models
+    }
+
+    /// Detect the models read/written by the contract code and compare them
+    /// with the declared dependencies, if any.
+    /// As reading a model does not require any permission, only undeclared
+    /// writes are reported.
+    fn analyze_dependencies(&mut self, db: &SimpleParserDatabase, body: &ast::ModuleBody) {
+        self.detected_dependencies =
+            DojoAnalyzer::find_model_dependencies(db, &body.as_syntax_node())
+                .into_iter()
+                .map(|d| Dependency {
+                    name: match &self.namespace {
+                        Some(namespace) => format!("{namespace}-{}", d.name),
+                        None => d.name,
+                    },
+                    ..d
+                })
+                .collect();
+
+        if self.dependencies.is_empty() {
+            return;
+        }
+
+        for detected in self.detected_dependencies.iter() {
+            let declared = self.dependencies.iter().find(|d| d.name == detected.name);
+
+            if detected.write && !declared.is_some_and(|d| d.write) {
+                self.diagnostics.push_warning(format!(
+                    "The model '{}' is written by the contract but is not declared in 'writes'.",
+                    detected.name
+                ));
+            }
+        }
+    }
+
+    /// Build the manifest exported for this contract, to be written by the
+    /// post-processing step of the macro.
+    fn build_manifest(&self, name: &str) -> ContractManifest {
+        ContractManifest {
+            name: name.to_string(),
+            namespace: self.namespace.clone(),
+            dependencies: self.dependencies.clone(),
+            detected_dependencies: self.detected_dependencies.clone(),
+            init_schema: self.init_schema.clone(),
+        }
+    }
+
+    /// Output the model dependencies declared in the contract attribute
+    /// as (model name or tag, read, write).
+    fn create_dependencies_fn(&self) -> TokenStream {
+        let dependencies = self
+            .dependencies
+            .iter()
+            .map(|d| format!("(\"{}\", {}, {})", d.name, d.read, d.write))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let dependencies = DojoTokenizer::tokenize(&dependencies);
+
+        quote! {
+            #[abi(per_item)]
+            #[generate_trait]
+            pub impl IDojoDependenciesImpl of IDojoDependencies {
+                #[external(v0)]
+                fn dojo_dependencies(self: @ContractState) -> Span<(ByteArray, bool, bool)> {
+                    let dependencies: Array<(ByteArray, bool, bool)> = array![#dependencies];
+                    dependencies.span()
+                }
+            }
+        }
+    }
+
+    fn generate_contract_code(&self, name: &String, body: Vec<TokenStream>) -> TokenStream {
         let contract_impl_name = DojoTokenizer::tokenize(&format!("{name}__ContractImpl"));
         let dojo_name = DojoTokenizer::tokenize(&format!("\"{name}\""));
         let name = DojoTokenizer::tokenize(name);
 
+        // with a declared namespace, the world storage of this namespace
+        // can be directly retrieved, unless the contract already defines
+        // its own `world_default` function.
+        let world_default_fn = match &self.namespace {
+            Some(namespace) if !self.has_world_default => format!(
+                "fn world_default(self: @ContractState) -> dojo::world::storage::WorldStorage {{
+                    self.world(@\"{namespace}\")
+                }}"
+            ),
+            _ => "".to_string(),
+        };
+        let world_default_fn = DojoTokenizer::tokenize(&world_default_fn);
+
         let mut content = TokenStream::new(vec![]);
         content.extend(body);
 
@@ -155,6 +488,8 @@ impl DojoContract {
                     fn world_ns_hash(self: @ContractState, namespace_hash: felt252) -> dojo::world::storage::WorldStorage {
                         dojo::world::WorldStorageTrait::new_from_hash(self.world_provider.world_dispatcher(), namespace_hash)
                     }
+
+                    #world_default_fn
                 }
 
                 #content
@@ -164,6 +499,12 @@ impl DojoContract {
 
     /// If a constructor is provided, we should keep the user statements.
     /// We only inject the world provider initializer.
+    /// Extra constructor parameters are only allowed with the `constructor_args` option.
+    ///
+    /// The world deploys contracts with an empty calldata in `register_contract` and
+    /// `upgrade_contract`, so a contract with extra constructor parameters can't be
+    /// registered through the world. Deploying it another way sets the deployer as the
+    /// world of the `world_provider`, hence the warning.
     fn handle_constructor_fn(
         &mut self,
         db: &SimpleParserDatabase,
@@ -171,11 +512,27 @@ impl DojoContract {
     ) -> TokenStream {
         self.has_constructor = true;
 
-        if !is_valid_constructor_params(db, fn_ast) {
+        if self.constructor_args {
+            if !is_valid_constructor_first_param(db, fn_ast) {
+                self.diagnostics.push_error(
+                    "The first parameter of the constructor must be `ref self: ContractState`."
+                        .to_string(),
+                );
+            } else if !is_valid_constructor_params(db, fn_ast) {
+                self.diagnostics.push_warning(
+                    "The world deploys contracts without constructor calldata, so a contract \
+                    with extra constructor parameters can't be registered or upgraded through \
+                    the world. If deployed another way, the deployer is used as the world of the \
+                    contract."
+                        .to_string(),
+                );
+            }
+        } else if !is_valid_constructor_params(db, fn_ast) {
             self.diagnostics.push_error(format!(
                 "The constructor must have exactly one parameter, which is `ref self: \
                     ContractState`. Add a `{DOJO_INIT_FN}` function instead if you need to \
-                    initialize the contract with parameters."
+                    initialize the contract with parameters, or set the `constructor_args` \
+                    option to forward extra parameters to the constructor."
             ));
         }
 
@@ -218,31 +575,146 @@ impl DojoContract {
             ));
         }
 
+        self.init_schema = self.build_init_fn_schema(db, fn_ast);
+
         let fn_decl = fn_ast.declaration(db).as_syntax_node();
         let fn_decl = SyntaxNodeWithDb::new(&fn_decl, db);
 
+        let fn_body = fn_ast.body(db).as_syntax_node();
+        let fn_body = SyntaxNodeWithDb::new(&fn_body, db);
+
+        let init_guard = self.create_init_guard();
+
         quote! {
             #[abi(per_item)]
             #[generate_trait]
             pub impl IDojoInitImpl of IDojoInit {
                 #[external(v0)]
                 #fn_decl {
-                    if starknet::get_caller_address() != self.world_provider.world_dispatcher().contract_address {
+                    #init_guard
+                    #fn_body
+                }
+            }
+        }
+    }
+
+    /// Build the check of the `dojo_init` caller, which must be the world, or an owner
+    /// of the contract resource if the `owner_can_init` option is set.
+    ///
+    /// The world only calls `dojo_init` once, but an owner could call it at any time.
+    /// So, with the `owner_can_init` option, an owner can only call `dojo_init` if the
+    /// contract has not been initialized yet. The world keeps track of its own calls in
+    /// `init_contract`, so its call always takes precedence, even after an owner one.
+    /// The flag is stored at a dedicated storage address, as `dojo_init` may receive
+    /// the contract state as a snapshot.
+    fn create_init_guard(&self) -> TokenStream {
+        match (&self.namespace, self.owner_can_init) {
+            (Some(namespace), true) => {
+                let selector = DojoTokenizer::tokenize(&format!(
+                    "{:#x}",
+                    naming::compute_selector_from_names(namespace, &self.name)
+                ));
+                let init_flag_address = DojoTokenizer::tokenize(&format!(
+                    "{:#x}",
+                    get_selector_from_name(DOJO_INIT_FLAG_STORAGE).unwrap()
+                ));
+
+                quote! {
+                    let caller = starknet::get_caller_address();
+                    let world = self.world_provider.world_dispatcher();
+                    let is_world = caller == world.contract_address;
+
+                    if !is_world
+                        && !dojo::world::IWorldDispatcherTrait::is_owner(world, #selector, caller) {
                         core::panics::panic_with_byte_array(
                             @format!(
-                                "Only the world can init contract `{}`, but caller is `{:?}`",
+                                "Only the world or an owner can init contract `{}`, but caller is `{:?}`",
                                 self.dojo_name(),
-                                starknet::get_caller_address()
+                                caller
                             )
                         );
                     }
+
+                    let init_flag_address = starknet::storage_access::storage_base_address_const::<#init_flag_address>();
+
+                    if !is_world && starknet::SyscallResultTrait::unwrap_syscall(
+                        starknet::Store::<bool>::read(0, init_flag_address)
+                    ) {
+                        core::panics::panic_with_byte_array(
+                            @format!("Contract `{}` is already initialized", self.dojo_name())
+                        );
+                    }
+
+                    starknet::SyscallResultTrait::unwrap_syscall(
+                        starknet::Store::<bool>::write(0, init_flag_address, true)
+                    );
+                }
+            }
+            _ => quote! {
+                if starknet::get_caller_address() != self.world_provider.world_dispatcher().contract_address {
+                    core::panics::panic_with_byte_array(
+                        @format!(
+                            "Only the world can init contract `{}`, but caller is `{:?}`",
+                            self.dojo_name(),
+                            starknet::get_caller_address()
+                        )
+                    );
+                }
+            },
+        }
+    }
+
+    /// Build the schema of the `dojo_init` parameters, to be exported in the manifest.
+    /// If a parameter type is not supported, no schema is exported.
+    fn build_init_fn_schema(
+        &mut self,
+        db: &SimpleParserDatabase,
+        fn_ast: &ast::FunctionWithBody,
+    ) -> Option<Ty> {
+        let mut members = vec![];
+
+        for param in fn_ast
+            .declaration(db)
+            .signature(db)
+            .parameters(db)
+            .elements(db)
+        {
+            let param = param.as_syntax_node().get_text_without_trivia(db);
+            let Some((name, ty)) = param.split_once(':') else {
+                continue;
+            };
+
+            // remove modifiers like `ref` or `mut`.
+            let name = name.split_whitespace().last().unwrap_or_default();
+
+            if name == "self" {
+                continue;
+            }
+
+            match build_init_param_ty(ty) {
+                Some(ty) => members.push(Member {
+                    name: name.to_string(),
+                    ty,
+                    key: false,
+                }),
+                None => {
+                    self.diagnostics.push_warning(format!(
+                        "The type '{}' of the {DOJO_INIT_FN} parameter '{name}' is not supported \
+                        by the init calldata encoder. Use primitive types, arrays, tuples or \
+                        ByteArray to export the {DOJO_INIT_FN} parameters.",
+                        ty.trim()
+                    ));
+                    return None;
                 }
             }
         }
+
+        Some(build_init_schema(members))
     }
 
     fn create_init_fn(&self) -> TokenStream {
         let init_name = DojoTokenizer::tokenize(DOJO_INIT_FN);
+        let init_guard = self.create_init_guard();
 
         quote! {
             #[abi(per_item)]
@@ -250,13 +722,7 @@ impl DojoContract {
             pub impl IDojoInitImpl of IDojoInit {
                 #[external(v0)]
                 fn #init_name(self: @ContractState) {
-                    if starknet::get_caller_address() != self.world_provider.world_dispatcher().contract_address {
-                        core::panics::panic_with_byte_array(
-                            @format!("Only the world can init contract `{}`, but caller is `{:?}`",
-                            self.dojo_name(),
-                            starknet::get_caller_address(),
-                        ));
-                    }
+                    #init_guard
                 }
             }
         }
@@ -269,27 +735,125 @@ impl DojoContract {
     ) -> TokenStream {
         self.has_event = true;
 
+        for variant in enum_ast.variants(db).elements(db) {
+            let variant_name = variant.name(db).text(db).to_string();
+
+            if DOJO_EVENT_VARIANTS.contains(&variant_name.as_str()) {
+                self.diagnostics.push_error(format!(
+                    "The variant '{variant_name}' of the contract 'Event' is reserved by \
+                    dojo::contract."
+                ));
+            }
+        }
+
+        let attributes = DojoTokenizer::tokenize(&merge_item_attributes(
+            db,
+            enum_ast.attributes(db),
+            "event",
+            &["Drop", "starknet::Event"],
+        ));
+
+        let visibility = enum_ast.visibility(db).as_syntax_node();
+        let visibility = SyntaxNodeWithDb::new(&visibility, db);
+
+        let user_variants = enum_ast
+            .variants(db)
+            .elements(db)
+            .iter()
+            .map(|v| v.name(db).text(db).to_string())
+            .collect::<Vec<_>>();
+        let component_variants = self.create_component_variants(&user_variants);
+
         let variants = enum_ast.variants(db).as_syntax_node();
         let variants = SyntaxNodeWithDb::new(&variants, db);
 
         quote! {
-            #[event]
-            #[derive(Drop, starknet::Event)]
-            enum Event {
+            #attributes
+            #visibility enum Event {
                 UpgradeableEvent: upgradeable_cpt::Event,
                 WorldProviderEvent: world_provider_cpt::Event,
+                #component_variants
                 #variants
             }
         }
     }
 
     pub fn create_event(&mut self) -> TokenStream {
+        let component_variants = self.create_component_variants(&[]);
+
         quote! {
             #[event]
             #[derive(Drop, starknet::Event)]
             enum Event {
                 UpgradeableEvent: upgradeable_cpt::Event,
                 WorldProviderEvent: world_provider_cpt::Event,
+                #component_variants
+            }
+        }
+    }
+
+    /// Build the event variants of the embedded components which are not
+    /// already declared by the user.
+    fn create_component_variants(&self, user_variants: &[String]) -> TokenTree {
+        let variants = self
+            .components
+            .iter()
+            .filter(|c| !c.event.is_empty() && !user_variants.contains(&c.event))
+            .map(|c| format!("#[flat]\n{}: {}::Event,\n", c.event, c.path))
+            .collect::<String>();
+
+        DojoTokenizer::tokenize(&variants)
+    }
+
+    /// Build the storage members of the embedded components which are not
+    /// already declared by the user.
+    fn create_component_storages(&self, user_members: &[String]) -> TokenTree {
+        let members = self
+            .components
+            .iter()
+            .filter(|c| !c.storage.is_empty() && !user_members.contains(&c.storage))
+            .map(|c| format!("#[substorage(v0)]\n{}: {}::Storage,\n", c.storage, c.path))
+            .collect::<String>();
+
+        DojoTokenizer::tokenize(&members)
+    }
+
+    /// Collect the components embedded with the `component!` inline macro,
+    /// to automatically add their storage and events to the contract ones.
+    fn collect_components(&mut self, db: &SimpleParserDatabase, body: &ast::ModuleBody) {
+        for item in body.items(db).elements(db) {
+            let ast::ModuleItem::InlineMacro(macro_ast) = item else {
+                continue;
+            };
+
+            if macro_ast.name(db).text(db) != "component" {
+                continue;
+            }
+
+            let ast::WrappedArgList::ParenthesizedArgList(args) = macro_ast.arguments(db) else {
+                continue;
+            };
+
+            let mut component = EmbeddedComponent::default();
+
+            for arg in args.arguments(db).elements(db) {
+                if let ast::ArgClause::Named(arg) = arg.arg_clause(db) {
+                    let value = arg.value(db).as_syntax_node().get_text_without_trivia(db);
+
+                    match arg.name(db).text(db).as_str() {
+                        "path" => component.path = value,
+                        "storage" => component.storage = value,
+                        "event" => component.event = value,
+                        _ => {}
+                    }
+                }
+            }
+
+            // Dojo components are already wired by the contract.
+            if !component.path.is_empty()
+                && !["upgradeable_cpt", "world_provider_cpt"].contains(&component.path.as_str())
+            {
+                self.components.push(component);
             }
         }
     }
@@ -301,22 +865,96 @@ impl DojoContract {
     ) -> TokenStream {
         self.has_storage = true;
 
+        for member in struct_ast.members(db).elements(db) {
+            let member_name = member.name(db).text(db).to_string();
+
+            if DOJO_STORAGE_MEMBERS.contains(&member_name.as_str()) {
+                self.diagnostics.push_error(format!(
+                    "The member '{member_name}' of the contract 'Storage' is reserved by \
+                    dojo::contract."
+                ));
+            }
+        }
+
+        let attributes = DojoTokenizer::tokenize(&merge_item_attributes(
+            db,
+            struct_ast.attributes(db),
+            "storage",
+            &[],
+        ));
+
+        let visibility = struct_ast.visibility(db).as_syntax_node();
+        let visibility = SyntaxNodeWithDb::new(&visibility, db);
+
+        let user_members = struct_ast
+            .members(db)
+            .elements(db)
+            .iter()
+            .map(|m| m.name(db).text(db).to_string())
+            .collect::<Vec<_>>();
+        let component_storages = self.create_component_storages(&user_members);
+
         let members = struct_ast.members(db).as_syntax_node();
         let members = SyntaxNodeWithDb::new(&members, db);
 
         quote! {
-            #[storage]
-            struct Storage {
+            #attributes
+            #visibility struct Storage {
                 #[substorage(v0)]
                 upgradeable: upgradeable_cpt::Storage,
                 #[substorage(v0)]
                 world_provider: world_provider_cpt::Storage,
+                #component_storages
                 #members
             }
         }
     }
 
+    /// Check that a `use` statement does not import an `Event` or a `Storage` item.
+    fn check_use(&mut self, db: &SimpleParserDatabase, use_ast: &ast::ItemUse) {
+        for leaf in use_ast
+            .as_syntax_node()
+            .descendants(db)
+            .filter(|n| n.kind(db) == SyntaxKind::UsePathLeaf)
+        {
+            let leaf = ast::UsePathLeaf::from_syntax_node(db, leaf);
+
+            let imported_name = match leaf.alias_clause(db) {
+                ast::OptionAliasClause::AliasClause(alias) => alias.alias(db).text(db).to_string(),
+                ast::OptionAliasClause::Empty(_) => {
+                    leaf.ident(db).as_syntax_node().get_text_without_trivia(db)
+                }
+            };
+
+            if imported_name == EVENT_ITEM || imported_name == STORAGE_ITEM {
+                self.report_unmergeable_item(&imported_name, "is imported with a `use` statement");
+            }
+        }
+    }
+
+    /// `Event` and `Storage` have to be declared in the contract module to be merged
+    /// with the Dojo ones. Otherwise, an error is reported and the Dojo item is not
+    /// generated, to avoid an additional error about a duplicated definition.
+    fn report_unmergeable_item(&mut self, item_name: &str, reason: &str) {
+        let hint = if item_name == EVENT_ITEM {
+            self.has_event = true;
+            "`enum Event` in the contract module (component events can be added as \
+            `#[flat]` variants)"
+        } else {
+            self.has_storage = true;
+            "`struct Storage` in the contract module (component storages can be added as \
+            `#[substorage(v0)]` members)"
+        };
+
+        self.diagnostics.push_error(format!(
+            "The contract '{item_name}' {reason} and cannot be merged with the Dojo one. \
+            Declare {hint} instead."
+        ));
+    }
+
     pub fn create_storage(&mut self) -> TokenStream {
+        let component_storages = self.create_component_storages(&[]);
+
         quote! {
             #[storage]
             struct Storage {
@@ -324,11 +962,96 @@ impl DojoContract {
                 upgradeable: upgradeable_cpt::Storage,
                 #[substorage(v0)]
                 world_provider: world_provider_cpt::Storage,
+                #component_storages
             }
         }
     }
 }
 
+/// Build the attributes of a merged `Event` or `Storage` item: the Dojo item attribute,
+/// the user derives merged with the Dojo ones, and the other user attributes as is.
+fn merge_item_attributes(
+    db: &SimpleParserDatabase,
+    attributes: ast::AttributeList,
+    item_attr: &str,
+    dojo_derives: &[&str],
+) -> String {
+    // `Event` and `starknet::Event` are the same derive.
+    let short_name = |d: &str| d.rsplit("::").next().unwrap_or(d).to_string();
+
+    let mut derives = dojo_derives
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>();
+    let mut other_attrs = vec![];
+
+    for attr in attributes.elements(db) {
+        let structured_attr = attr.clone().structurize(db);
+
+        if structured_attr.id == item_attr {
+            continue;
+        }
+
+        if structured_attr.id != "derive" {
+            other_attrs.push(attr.as_syntax_node().get_text_without_trivia(db));
+            continue;
+        }
+
+        for arg in structured_attr.args {
+            let derive = arg.arg.as_syntax_node().get_text_without_trivia(db);
+
+            if !derives.iter().any(|d| short_name(d) == short_name(&derive)) {
+                derives.push(derive);
+            }
+        }
+    }
+
+    let mut merged_attrs = vec![format!("#[{item_attr}]")];
+
+    if !derives.is_empty() {
+        merged_attrs.push(format!("#[derive({})]", derives.join(", ")));
+    }
+
+    merged_attrs.extend(other_attrs);
+    merged_attrs.join("\n")
+}
+
+/// Build the schema of the `dojo_init` parameters, as a struct
+/// with one member per parameter.
+fn build_init_schema(members: Vec<Member>) -> Ty {
+    Ty::Struct(Struct {
+        name: DOJO_INIT_FN.to_string(),
+        children: members,
+    })
+}
+
+/// Build the `Ty` describing a `dojo_init` parameter type,
+/// or `None` if the type is not supported (custom types).
+fn build_init_param_ty(ty: &str) -> Option<Ty> {
+    let ty = ty.trim();
+
+    if is_byte_array(ty) {
+        return Some(Ty::ByteArray(String::new()));
+    }
+
+    if is_array(ty) {
+        return build_init_param_ty(&get_array_item_type(ty)).map(|t| Ty::Array(vec![t]));
+    }
+
+    if is_tuple(ty) {
+        return get_tuple_item_types(ty)
+            .iter()
+            .map(|t| build_init_param_ty(t))
+            .collect::<Option<Vec<_>>>()
+            .map(Ty::Tuple);
+    }
+
+    // remove the module path (i.e `starknet::ContractAddress`).
+    let ty = ty.rsplit("::").next().unwrap_or(ty);
+
+    Primitive::from_str(ty).ok().map(Ty::Primitive)
+}
+
 /// Checks if the constructor parameters are valid.
 /// We only allow one parameter for the constructor, which is the contract state,
 /// since `dojo_init` is called by the world after every resource has been deployed.
@@ -347,6 +1070,39 @@ fn is_valid_constructor_params(db: &SimpleParserDatabase, fn_ast: &ast::Function
             .contains("ref self: ContractState")
 }
 
+/// Checks if the first constructor parameter is the contract state,
+/// when extra constructor parameters are allowed.
+fn is_valid_constructor_first_param(
+    db: &SimpleParserDatabase,
+    fn_ast: &ast::FunctionWithBody,
+) -> bool {
+    fn_ast
+        .declaration(db)
+        .signature(db)
+        .parameters(db)
+        .elements(db)
+        .first()
+        .is_some_and(|p| {
+            p.as_syntax_node()
+                .get_text(db)
+                .contains("ref self: ContractState")
+        })
+}
+
+/// Checks if a function with the given name is defined in the module body,
+/// either as a free function or in an impl or a trait.
+fn defines_function(db: &SimpleParserDatabase, body: &ast::ModuleBody, fn_name: &str) -> bool {
+    body.as_syntax_node()
+        .descendants(db)
+        .filter(|n| n.kind(db) == SyntaxKind::FunctionDeclaration)
+        .any(|n| {
+            ast::FunctionDeclaration::from_syntax_node(db, n)
+                .name(db)
+                .text(db)
+                == fn_name
+        })
+}
+
 /* TODO RBA/
 #[cfg(test)]
 mod tests {
@@ -369,3 +1125,255 @@ mod tests {
     }
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_lang_macro::Severity;
+
+    fn process(args: &str, input: &str) -> ProcMacroResult {
+        DojoContract::process(
+            TokenStream::new(vec![DojoTokenizer::tokenize(args)]),
+            TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+    }
+
+    #[test]
+    fn test_contract_dependencies() {
+        let res = process(
+            "(namespace: \"ns\", reads: [Position, \"other-Moves\"], writes: [Position])",
+            "mod actions { fn f() {} }",
+        );
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("(\"ns-Position\", true, true), (\"other-Moves\", true, false)"));
+        assert!(output.contains("self.world(@\"ns\")"));
+    }
+
+    #[test]
+    fn test_user_defined_world_default() {
+        let res = process(
+            "(namespace: \"ns\")",
+            "mod actions {
+                #[generate_trait]
+                impl InternalImpl of InternalTrait {
+                    fn world_default(self: @ContractState) -> dojo::world::WorldStorage {
+                        self.world(@\"other_ns\")
+                    }
+                }
+            }",
+        );
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(!output.contains("self.world(@\"ns\")"));
+        assert_eq!(output.matches("fn world_default").count(), 1);
+    }
+
+    #[test]
+    fn test_contract_detected_dependencies() {
+        let res = process(
+            "(namespace: \"ns\", writes: [Position])",
+            "mod actions {
+                fn move(ref self: ContractState) {
+                    let mut world = self.world_default();
+                    let position: Position = world.read_model(player);
+                    world.write_model(@position);
+                    world.write_model(@Moves { player, remaining: 1 });
+                }
+            }",
+        );
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(res.diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The model 'ns-Moves' is written by the contract but is not declared in 'writes'."
+        );
+    }
+
+    #[test]
+    fn test_build_init_param_ty() {
+        assert_eq!(
+            build_init_param_ty("u8"),
+            Some(Ty::Primitive(Primitive::U8(None)))
+        );
+        assert_eq!(
+            build_init_param_ty("starknet::ContractAddress"),
+            Some(Ty::Primitive(Primitive::ContractAddress(None)))
+        );
+        assert_eq!(
+            build_init_param_ty("Span<(felt252, ByteArray)>"),
+            Some(Ty::Array(vec![Ty::Tuple(vec![
+                Ty::Primitive(Primitive::Felt252(None)),
+                Ty::ByteArray(String::new())
+            ])]))
+        );
+        assert_eq!(build_init_param_ty("Array<Position>"), None);
+    }
+
+    #[test]
+    fn test_contract_bad_args() {
+        let res = process(
+            "(namespace: \"n s\", writes: [\"ns-\"], other: 1)",
+            "mod actions { fn f() {} }",
+        );
+
+        assert_eq!(res.diagnostics.len(), 3);
+        assert!(res
+            .diagnostics
+            .iter()
+            .all(|d| d.severity == Severity::Error));
+        assert_eq!(
+            res.diagnostics[1].message,
+            "Invalid model 'ns-' in 'writes'. A model must be identified by its name or by \
+            its tag (`namespace-name`)."
+        );
+    }
+
+    #[test]
+    fn test_contract_init_options() {
+        let res = process(
+            "(namespace: \"ns\", constructor_args: true, owner_can_init: true)",
+            "mod actions {
+                fn constructor(ref self: ContractState, admin: ContractAddress) {
+                    self.admin.write(admin);
+                }
+
+                fn dojo_init(self: @ContractState, value: u8) {
+                    self.value.write(value);
+                }
+            }",
+        );
+        let output = res.token_stream.to_string();
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(res.diagnostics[0].severity, Severity::Warning);
+        assert!(res.diagnostics[0]
+            .message
+            .starts_with("The world deploys contracts without constructor calldata"));
+        assert!(output.contains("admin: ContractAddress"));
+        assert!(output.contains("self.value.write(value);"));
+        assert!(output.contains("is_owner"));
+        assert!(output.contains("is already initialized"));
+    }
+
+    #[test]
+    fn test_contract_owner_can_init_without_namespace() {
+        let res = process("(owner_can_init: true)", "mod actions { fn f() {} }");
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The 'owner_can_init' option requires the contract 'namespace' to be declared."
+        );
+    }
+
+    #[test]
+    fn test_contract_merge_event_and_storage() {
+        let res = process(
+            "",
+            "mod actions {
+                #[event]
+                #[derive(Drop, starknet::Event, PartialEq)]
+                #[custom_attr]
+                pub enum Event {
+                    #[flat]
+                    ERC20Event: ERC20Component::Event,
+                }
+
+                #[storage]
+                struct Storage {
+                    #[substorage(v0)]
+                    erc20: ERC20Component::Storage,
+                }
+            }",
+        );
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("#[derive(Drop, starknet::Event, PartialEq)]\n#[custom_attr]"));
+        assert!(output.contains("#[flat]"));
+        assert!(output.contains("erc20: ERC20Component::Storage"));
+        // the Dojo items are merged, not generated a second time.
+        assert_eq!(output.matches("ERC20Event").count(), 1);
+        assert_eq!(output.matches("world_provider_cpt::Storage").count(), 1);
+    }
+
+    #[test]
+    fn test_contract_unmergeable_event_and_storage() {
+        let res = process(
+            "",
+            "mod actions {
+                use super::events::Event;
+                type Storage = super::Storage;
+
+                #[derive(Drop, starknet::Event)]
+                enum Other {
+                    UpgradeableEvent: u8,
+                }
+            }",
+        );
+        let output = res.token_stream.to_string();
+
+        assert_eq!(res.diagnostics.len(), 2);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The contract 'Event' is imported with a `use` statement and cannot be merged \
+            with the Dojo one. Declare `enum Event` in the contract module (component events \
+            can be added as `#[flat]` variants) instead."
+        );
+        assert_eq!(
+            res.diagnostics[1].message,
+            "The contract 'Storage' is declared as a type alias and cannot be merged with the \
+            Dojo one. Declare `struct Storage` in the contract module (component storages can \
+            be added as `#[substorage(v0)]` members) instead."
+        );
+        assert!(!output.contains("upgradeable_cpt::Event"));
+        assert!(!output.contains("upgradeable_cpt::Storage"));
+    }
+
+    #[test]
+    fn test_contract_reserved_storage_member() {
+        let res = process(
+            "",
+            "mod actions {
+                #[storage]
+                struct Storage {
+                    world_provider: u8,
+                }
+            }",
+        );
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The member 'world_provider' of the contract 'Storage' is reserved by dojo::contract."
+        );
+    }
+
+    #[test]
+    fn test_contract_embedded_components() {
+        let res = process(
+            "",
+            "mod actions {
+                component!(path: score_cpt, storage: score, event: ScoreEvent);
+                component!(path: erc20_cpt, storage: erc20, event: ERC20Event);
+
+                #[storage]
+                struct Storage {
+                    #[substorage(v0)]
+                    erc20: erc20_cpt::Storage,
+                }
+            }",
+        );
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("#[substorage(v0)]\nscore: score_cpt::Storage,"));
+        assert!(output.contains("#[flat]\nScoreEvent: score_cpt::Event,"));
+        assert!(output.contains("#[flat]\nERC20Event: erc20_cpt::Event,"));
+        assert_eq!(output.matches("erc20: erc20_cpt::Storage").count(), 1);
+    }
+}
@@ -4,7 +4,7 @@ use cairo_lang_syntax::node::{ast, helpers::QueryAttrs, TypedSyntaxNode};
 
 use crate::constants::{DOJO_INTROSPECT_DERIVE, DOJO_PACKED_DERIVE, EXPECTED_DERIVE_ATTR_NAMES};
 use crate::helpers::{
-    self, DiagnosticsExt, DojoChecker, DojoFormatter, DojoParser, DojoTokenizer, Member,
+    self, registry, DiagnosticsExt, DojoChecker, DojoFormatter, DojoParser, DojoTokenizer, Member,
     ProcMacroResultExt,
 };
 
@@ -56,6 +56,8 @@ impl DojoEvent {
             return failure;
         }
 
+        registry::register_name(&event.event_name);
+
         let members = DojoParser::parse_members(
             db,
             &struct_ast.members(db).elements(db),
@@ -201,6 +203,12 @@ pub impl {type_name}ModelParser of dojo::model::model::ModelParser<{type_name}>
         {serialized_values}
         core::array::ArrayTrait::span(@serialized)
     }}
+    fn deserialize(ref keys: Span<felt252>, ref values: Span<felt252>) -> Option<{type_name}> {{
+        let mut serialized: Array<felt252> = keys.into();
+        serialized.append_span(values);
+        let mut serialized = serialized.span();
+        core::serde::Serde::<{type_name}>::deserialize(ref serialized)
+    }}
 }}
 
 pub impl {type_name}EventImpl = dojo::event::event::EventImpl<{type_name}>;
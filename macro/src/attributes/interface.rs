@@ -0,0 +1,169 @@
+use cairo_lang_macro::{quote, Diagnostic, ProcMacroResult, TokenStream};
+use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::attribute::structured::{AttributeArg, AttributeArgVariant};
+use cairo_lang_syntax::node::with_db::SyntaxNodeWithDb;
+use cairo_lang_syntax::node::{ast, Terminal, TypedSyntaxNode};
+
+use dojo_types::naming;
+
+use crate::helpers::{
+    dispatcher_fn_name, registry, DiagnosticsExt, DojoParser, DojoTokenizer, ProcMacroResultExt,
+};
+
+#[derive(Debug)]
+pub struct DojoInterface {
+    diagnostics: Vec<Diagnostic>,
+    tag: Option<String>,
+}
+
+impl DojoInterface {
+    pub fn new() -> Self {
+        Self {
+            diagnostics: vec![],
+            tag: None,
+        }
+    }
+
+    pub fn process(args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
+        let db = SimpleParserDatabase::default();
+
+        if let Some(trait_ast) = DojoParser::parse_and_find_trait(&db, &token_stream) {
+            let args = DojoParser::parse_attribute_args(&db, &args);
+            return DojoInterface::process_ast(&db, &trait_ast, &args);
+        }
+
+        ProcMacroResult::fail("'dojo::interface' must be used on trait only.".to_string())
+    }
+
+    fn process_ast(
+        db: &SimpleParserDatabase,
+        trait_ast: &ast::ItemTrait,
+        args: &[AttributeArg],
+    ) -> ProcMacroResult {
+        let mut interface = DojoInterface::new();
+
+        interface.parse_args(db, args);
+
+        let Some(tag) = interface.tag.clone() else {
+            interface.diagnostics.push_error(
+                "The dojo::interface attribute requires the tag of the contract implementing \
+                the interface (i.e #[dojo::interface(tag: \"ns-actions\")])."
+                    .to_string(),
+            );
+            return ProcMacroResult::fail_with_diagnostics(interface.diagnostics);
+        };
+
+        let interface_name = trait_ast.name(db).text(db).to_string();
+
+        let visibility = trait_ast.visibility(db).as_syntax_node();
+        let visibility = SyntaxNodeWithDb::new(&visibility, db);
+
+        let trait_node = trait_ast.as_syntax_node();
+        let trait_node = SyntaxNodeWithDb::new(&trait_node, db);
+
+        let dispatcher_fn = DojoTokenizer::tokenize(&dispatcher_fn_name(&interface_name));
+        let dispatcher = DojoTokenizer::tokenize(&format!("{interface_name}Dispatcher"));
+
+        // the selector is computed at compile time, so the world lookup is a single call.
+        // Only contracts are matched, as other resources (i.e libraries) have no address.
+        let selector =
+            DojoTokenizer::tokenize(&format!("{:#x}", naming::compute_selector_from_tag(&tag)));
+        let not_registered = DojoTokenizer::tokenize(&format!(
+            "\"Contract `{tag}` is not registered in the world.\""
+        ));
+
+        let interface_code = quote! {
+            #[starknet::interface]
+            #trait_node
+
+            #visibility fn #dispatcher_fn(world: @dojo::world::storage::WorldStorage) -> #dispatcher {
+                match dojo::world::IWorldDispatcherTrait::resource(*world.dispatcher, #selector) {
+                    dojo::world::Resource::Contract((contract_address, _)) => #dispatcher { contract_address },
+                    _ => core::panics::panic_with_byte_array(@#not_registered),
+                }
+            }
+        };
+
+        ProcMacroResult::finalize(interface_code, interface.diagnostics)
+    }
+
+    /// Parse the arguments of the interface attribute:
+    /// - `tag`: the tag of the contract implementing the interface (`namespace-name`).
+    fn parse_args(&mut self, db: &SimpleParserDatabase, args: &[AttributeArg]) {
+        for arg in args {
+            match &arg.variant {
+                AttributeArgVariant::Named { name, value } if name.text == "tag" => {
+                    let ast::Expr::String(s) = value else {
+                        self.diagnostics
+                            .push_error("The interface tag must be a string.".to_string());
+                        continue;
+                    };
+
+                    let tag = s.text(db).to_string().replace("\"", "");
+
+                    if naming::is_valid_tag(&tag) {
+                        if let Some(message) = registry::check_tag(&tag) {
+                            self.diagnostics.push_warning(message);
+                        }
+
+                        self.tag = Some(tag);
+                    } else {
+                        self.diagnostics.push_error(format!(
+                            "Invalid tag '{tag}' for dojo::interface. A contract tag must be \
+                            `namespace-name`, where namespace and name can only contain \
+                            characters (a-z/A-Z), digits (0-9) and underscore (_)."
+                        ));
+                    }
+                }
+                _ => self.diagnostics.push_error(format!(
+                    "Unexpected argument '{}' for dojo::interface. Expected arguments: tag.",
+                    arg.arg.as_syntax_node().get_text_without_trivia(db)
+                )),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(args: &str, input: &str) -> ProcMacroResult {
+        DojoInterface::process(
+            TokenStream::new(vec![DojoTokenizer::tokenize(args)]),
+            TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+    }
+
+    #[test]
+    fn test_interface_dispatcher() {
+        let res = process(
+            "(tag: \"ns-actions\")",
+            "pub trait IActions<T> {
+                fn spawn(ref self: T);
+            }",
+        );
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("actions_dispatcher"));
+        assert!(output.contains(&format!(
+            "{:#x}",
+            naming::compute_selector_from_tag("ns-actions")
+        )));
+        assert!(output.contains("Contract `ns-actions` is not registered in the world."));
+        // libraries are not contracts, so they fall in the not registered case.
+        let output = output.replace(char::is_whitespace, "");
+        assert!(output.contains("dojo::world::Resource::Contract((contract_address,_))"));
+    }
+
+    #[test]
+    fn test_interface_bad_tag() {
+        let res = process("(tag: \"actions\")", "trait IActions<T> {}");
+
+        assert_eq!(res.diagnostics.len(), 2);
+        assert!(res.diagnostics[0]
+            .message
+            .starts_with("Invalid tag 'actions'"));
+    }
+}
@@ -1,5 +1,10 @@
+use std::collections::HashMap;
+
 use cairo_lang_macro::{quote, Diagnostic, ProcMacroResult, TokenStream};
 use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::attribute::structured::{AttributeArg, AttributeArgVariant};
+use cairo_lang_syntax::node::helpers::QueryAttrs;
+use cairo_lang_syntax::node::kind::SyntaxKind;
 use cairo_lang_syntax::node::with_db::SyntaxNodeWithDb;
 use cairo_lang_syntax::node::Terminal;
 use cairo_lang_syntax::node::{
@@ -7,8 +12,17 @@ use cairo_lang_syntax::node::{
     TypedSyntaxNode,
 };
 
+use dojo_types::naming;
+
 use crate::constants::{CONSTRUCTOR_FN, DOJO_INIT_FN};
-use crate::helpers::{DojoChecker, DojoParser, DojoTokenizer, ProcMacroResultExt};
+use crate::helpers::{
+    dispatcher_fn_name, registry, DiagnosticsExt, DojoChecker, DojoParser, DojoTokenizer,
+    ProcMacroResultExt,
+};
+
+/// Members of `self` which rely on the library storage, and thus cannot be used
+/// when the library is executed through a library call.
+const STORAGE_DEPENDENT_ACCESSES: [&str; 3] = ["world_provider", "world", "world_ns_hash"];
 
 #[derive(Debug)]
 pub struct DojoLibrary {
@@ -17,6 +31,9 @@ pub struct DojoLibrary {
     has_storage: bool,
     has_init: bool,
     has_constructor: bool,
+    version: Option<String>,
+    interfaces: Vec<String>,
+    imports: HashMap<String, String>,
 }
 
 impl DojoLibrary {
@@ -27,20 +44,28 @@ impl DojoLibrary {
             has_storage: false,
             has_init: false,
             has_constructor: false,
+            version: None,
+            interfaces: vec![],
+            imports: HashMap::new(),
         }
     }
 
-    pub fn process(token_stream: TokenStream) -> ProcMacroResult {
+    pub fn process(args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
         let db = SimpleParserDatabase::default();
 
         if let Some(module_ast) = DojoParser::parse_and_find_module(&db, &token_stream) {
-            return DojoLibrary::process_ast(&db, &module_ast);
+            let args = DojoParser::parse_attribute_args(&db, &args);
+            return DojoLibrary::process_ast(&db, &module_ast, &args);
         }
 
         ProcMacroResult::fail("'dojo::library' must be used on module only.".to_string())
     }
 
-    fn process_ast(db: &SimpleParserDatabase, module_ast: &ast::ItemModule) -> ProcMacroResult {
+    fn process_ast(
+        db: &SimpleParserDatabase,
+        module_ast: &ast::ItemModule,
+        args: &[AttributeArg],
+    ) -> ProcMacroResult {
         let mut library = DojoLibrary::new();
 
         let name = module_ast.name(db).text(db).to_string();
@@ -49,7 +74,12 @@ impl DojoLibrary {
             return failure;
         }
 
+        library.parse_args(db, &name, args);
+
+        registry::register_name(&library.dojo_name(&name));
+
         if let MaybeModuleBody::Some(body) = module_ast.body(db) {
+            library.check_storage_accesses(db, &name, &body);
             let mut body_nodes: Vec<_> = body
                 .items(db)
                 .elements(db)
@@ -63,7 +93,20 @@ impl DojoLibrary {
                         }
                         ast::ModuleItem::Struct(ref struct_ast) => {
                             if struct_ast.name(db).text(db).to_string() == "Storage" {
-                                return library.merge_storage(db, struct_ast.clone());
+                                return library.merge_storage(db, &name, struct_ast.clone());
+                            }
+                        }
+                        ast::ModuleItem::Use(ref use_ast) => {
+                            collect_imports(db, "", &use_ast.use_path(db), &mut library.imports);
+                        }
+                        ast::ModuleItem::Impl(ref impl_ast) => {
+                            if impl_ast.has_attr_with_arg(db, "abi", "embed_v0") {
+                                library.interfaces.push(
+                                    impl_ast
+                                        .trait_path(db)
+                                        .as_syntax_node()
+                                        .get_text_without_trivia(db),
+                                );
                             }
                         }
                         ast::ModuleItem::FreeFunction(ref fn_ast) => {
@@ -106,16 +149,164 @@ impl DojoLibrary {
                 body_nodes.push(library.create_storage())
             }
 
-            let library_code = DojoLibrary::generate_library_code(&name, body_nodes);
+            if let Some(version) = &library.version {
+                body_nodes.push(library.create_resolver(&name, version));
+            }
+
+            let library_code = library.generate_library_code(&name, body_nodes);
             return ProcMacroResult::finalize(library_code, library.diagnostics);
         }
 
         ProcMacroResult::fail(format!("The library '{name}' is empty."))
     }
 
-    fn generate_library_code(name: &String, body: Vec<TokenStream>) -> TokenStream {
+    /// Returns the name of the library resource. As the world registers a versioned
+    /// library as `<name>_v<version>`, dots of the version are replaced by underscores
+    /// (i.e `0.1.0` -> `simple_math_v0_1_0`).
+    fn dojo_name(&self, name: &str) -> String {
+        match &self.version {
+            Some(version) => format!("{name}_v{}", version.replace('.', "_")),
+            None => name.to_string(),
+        }
+    }
+
+    /// Parse the arguments of the library attribute:
+    /// - `version`: the library version, as provided to `IWorld::register_library`.
+    fn parse_args(&mut self, db: &SimpleParserDatabase, name: &str, args: &[AttributeArg]) {
+        for arg in args {
+            match &arg.variant {
+                AttributeArgVariant::Named {
+                    name: arg_name,
+                    value,
+                } if arg_name.text == "version" => {
+                    let ast::Expr::String(s) = value else {
+                        self.diagnostics
+                            .push_error("The library version must be a string.".to_string());
+                        continue;
+                    };
+
+                    let version = s.text(db).to_string().replace("\"", "");
+
+                    if naming::is_name_valid(&format!("{name}_v{}", version.replace('.', "_"))) {
+                        self.version = Some(version);
+                    } else {
+                        self.diagnostics.push_error(format!(
+                            "The library version '{version}' can only contain characters \
+                            (a-z/A-Z), digits (0-9), dots (.) and underscore (_)."
+                        ));
+                    }
+                }
+                _ => self.diagnostics.push_error(format!(
+                    "Unexpected argument '{}' for dojo::library. Expected arguments: version.",
+                    arg.arg.as_syntax_node().get_text_without_trivia(db)
+                )),
+            }
+        }
+    }
+
+    /// A library is only executed through library calls, in the context of the calling
+    /// contract. So, the library code cannot rely on its own storage to access the world.
+    fn check_storage_accesses(
+        &mut self,
+        db: &SimpleParserDatabase,
+        name: &str,
+        body: &ast::ModuleBody,
+    ) {
+        let mut reported = vec![];
+
+        for n in body
+            .as_syntax_node()
+            .descendants(db)
+            .filter(|n| n.kind(db) == SyntaxKind::ExprBinary)
+        {
+            let binary = ast::ExprBinary::from_syntax_node(db, n);
+
+            if !matches!(binary.op(db), ast::BinaryOperator::Dot(_))
+                || binary.lhs(db).as_syntax_node().get_text_without_trivia(db) != "self"
+            {
+                continue;
+            }
+
+            let accessed = match binary.rhs(db) {
+                ast::Expr::FunctionCall(call) => {
+                    call.path(db).as_syntax_node().get_text_without_trivia(db)
+                }
+                rhs => rhs.as_syntax_node().get_text_without_trivia(db),
+            };
+
+            if STORAGE_DEPENDENT_ACCESSES.contains(&accessed.as_str())
+                && !reported.contains(&accessed)
+            {
+                self.diagnostics.push_error(format!(
+                    "The library '{name}' cannot use `self.{accessed}`, as a library is only \
+                    executed through library calls and has no storage. Pass the world storage \
+                    as a function parameter instead."
+                ));
+                reported.push(accessed);
+            }
+        }
+    }
+
+    /// Generate the resolution of the library class hash from the world,
+    /// by name and version, and a typed library dispatcher for each
+    /// embedded interface.
+    fn create_resolver(&self, name: &str, version: &str) -> TokenStream {
+        let dojo_name = self.dojo_name(name);
+        let dispatchers = self
+            .interfaces
+            .iter()
+            .map(|interface| {
+                let interface = interface.split('<').next().unwrap_or(interface).trim();
+                let trait_name = interface.rsplit("::").next().unwrap_or(interface);
+
+                // the dispatcher is generated next to the interface, so an imported
+                // interface has to be resolved from its import path.
+                let interface = match interface.split_once("::") {
+                    Some((first, rest)) => match self.imports.get(first) {
+                        Some(path) => format!("{path}::{rest}"),
+                        None => interface.to_string(),
+                    },
+                    None => self
+                        .imports
+                        .get(interface)
+                        .cloned()
+                        .unwrap_or(interface.to_string()),
+                };
+
+                format!(
+                    "pub fn {}(world: @dojo::world::storage::WorldStorage) -> {interface}LibraryDispatcher {{
+                        {interface}LibraryDispatcher {{ class_hash: DojoLibraryResolverImpl::class_hash(world) }}
+                    }}
+                    ",
+                    dispatcher_fn_name(trait_name)
+                )
+            })
+            .collect::<String>();
+
+        TokenStream::new(vec![DojoTokenizer::tokenize(&format!(
+            "#[generate_trait]
+            pub impl DojoLibraryResolverImpl of DojoLibraryResolverTrait {{
+                fn version() -> ByteArray {{
+                    \"{version}\"
+                }}
+
+                fn class_hash(world: @dojo::world::storage::WorldStorage) -> starknet::ClassHash {{
+                    match dojo::world::WorldStorageTrait::dns_class_hash(world, @\"{dojo_name}\") {{
+                        Option::Some(class_hash) => class_hash,
+                        Option::None => core::panics::panic_with_byte_array(
+                            @\"Library `{dojo_name}` is not registered in the world namespace.\"
+                        ),
+                    }}
+                }}
+            }}
+
+            {dispatchers}"
+        ))])
+    }
+
+    fn generate_library_code(&self, name: &String, body: Vec<TokenStream>) -> TokenStream {
         let library_impl_name = DojoTokenizer::tokenize(&format!("{name}__LibraryImpl"));
-        let dojo_name = DojoTokenizer::tokenize(&format!("\"{name}\""));
+        let dojo_name = DojoTokenizer::tokenize(&format!("\"{}\"", self.dojo_name(name)));
         let name = DojoTokenizer::tokenize(name);
 
         let mut content = TokenStream::new(vec![]);
@@ -143,13 +334,6 @@ impl DojoLibrary {
                     }
                 }
 
-                #[generate_trait]
-                impl DojoLibraryInternalImpl of DojoLibraryInternalTrait {
-                    fn world(self: @ContractState, namespace: @ByteArray) -> dojo::world::storage::WorldStorage {
-                        dojo::world::WorldStorageTrait::new(self.world_provider.world_dispatcher(), namespace)
-                    }
-                }
-
                 #content
             }
         }
@@ -189,10 +373,19 @@ impl DojoLibrary {
     pub fn merge_storage(
         &mut self,
         db: &SimpleParserDatabase,
+        name: &str,
         struct_ast: ast::ItemStruct,
     ) -> TokenStream {
         self.has_storage = true;
 
+        for member in struct_ast.members(db).elements(db) {
+            self.diagnostics.push_error(format!(
+                "The library '{name}' cannot have the storage member '{}', as a library is \
+                only executed through library calls, in the context of the calling contract.",
+                member.name(db).text(db)
+            ));
+        }
+
         let members = struct_ast.members(db).as_syntax_node();
         let members = SyntaxNodeWithDb::new(&members, db);
 
@@ -216,3 +409,111 @@ impl DojoLibrary {
         }
     }
 }
+
+/// Collect the items imported by a `use` statement, with their full path
+/// (i.e `use super::{IMyLib, other::Other as O};` -> `IMyLib`: `super::IMyLib`,
+/// `O`: `super::other::Other`).
+fn collect_imports(
+    db: &SimpleParserDatabase,
+    prefix: &str,
+    use_path: &ast::UsePath,
+    imports: &mut HashMap<String, String>,
+) {
+    match use_path {
+        ast::UsePath::Leaf(leaf) => {
+            let ident = leaf.ident(db).as_syntax_node().get_text_without_trivia(db);
+            let imported_name = match leaf.alias_clause(db) {
+                ast::OptionAliasClause::AliasClause(alias) => alias.alias(db).text(db).to_string(),
+                ast::OptionAliasClause::Empty(_) => ident.clone(),
+            };
+
+            imports.insert(imported_name, format!("{prefix}{ident}"));
+        }
+        ast::UsePath::Single(single) => {
+            let ident = single
+                .ident(db)
+                .as_syntax_node()
+                .get_text_without_trivia(db);
+            collect_imports(
+                db,
+                &format!("{prefix}{ident}::"),
+                &single.use_path(db),
+                imports,
+            );
+        }
+        ast::UsePath::Multi(multi) => {
+            for use_path in multi.use_paths(db).elements(db) {
+                collect_imports(db, prefix, &use_path, imports);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(args: &str, input: &str) -> ProcMacroResult {
+        DojoLibrary::process(
+            TokenStream::new(vec![DojoTokenizer::tokenize(args)]),
+            TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+    }
+
+    #[test]
+    fn test_library_version_and_dispatcher() {
+        let res = process(
+            "(version: \"0.1.0\")",
+            "mod simple_math {
+                use super::SimpleMath;
+
+                #[abi(embed_v0)]
+                impl SimpleMathImpl of SimpleMath<ContractState> {
+                    fn one(self: @ContractState) -> u8 { 1 }
+                }
+            }",
+        );
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("\"simple_math_v0_1_0\""));
+        // the declared version is kept as is.
+        assert!(output.contains("\"0.1.0\""));
+        assert!(output.contains(
+            "pub fn simple_math_dispatcher(world: @dojo::world::storage::WorldStorage) -> \
+            super::SimpleMathLibraryDispatcher"
+        ));
+    }
+
+    #[test]
+    fn test_library_storage_dependent_code() {
+        let res = process(
+            "",
+            "mod simple_math {
+                #[storage]
+                struct Storage {
+                    value: u8,
+                }
+
+                fn f(self: @ContractState) {
+                    let world = self.world(@\"ns\");
+                    let dispatcher = self.world_provider.world_dispatcher();
+                }
+            }",
+        );
+
+        assert_eq!(res.diagnostics.len(), 3);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The library 'simple_math' cannot use `self.world`, as a library is only executed \
+            through library calls and has no storage. Pass the world storage as a function \
+            parameter instead."
+        );
+        assert_eq!(
+            res.diagnostics[2].message,
+            "The library 'simple_math' cannot have the storage member 'value', as a library is \
+            only executed through library calls, in the context of the calling contract."
+        );
+    }
+}
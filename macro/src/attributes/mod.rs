@@ -1,8 +1,10 @@
 use crate::helpers::debug_macro;
 use cairo_lang_macro::{attribute_macro, ProcMacroResult, TokenStream};
 
+pub mod component;
 pub mod contract;
 pub mod event;
+pub mod interface;
 pub mod library;
 pub mod model;
 
@@ -23,17 +25,33 @@ pub fn event(_args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
 }
 
 #[attribute_macro(parent = "dojo")]
-pub fn contract(_args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
-    let output = contract::DojoContract::process(token_stream);
+pub fn contract(args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
+    let output = contract::DojoContract::process(args, token_stream);
 
     debug_macro("contract", &output);
     output
 }
 
 #[attribute_macro(parent = "dojo")]
-pub fn library(_args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
-    let output = library::DojoLibrary::process(token_stream);
+pub fn library(args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
+    let output = library::DojoLibrary::process(args, token_stream);
 
     debug_macro("library", &output);
     output
 }
+
+#[attribute_macro(parent = "dojo")]
+pub fn component(_args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
+    let output = component::DojoComponent::process(token_stream);
+
+    debug_macro("component", &output);
+    output
+}
+
+#[attribute_macro(parent = "dojo")]
+pub fn interface(args: TokenStream, token_stream: TokenStream) -> ProcMacroResult {
+    let output = interface::DojoInterface::process(args, token_stream);
+
+    debug_macro("interface", &output);
+    output
+}
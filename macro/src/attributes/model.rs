@@ -6,8 +6,9 @@ use cairo_lang_syntax::node::helpers::QueryAttrs;
 use cairo_lang_syntax::node::{ast, TypedSyntaxNode};
 
 use crate::constants::{DOJO_INTROSPECT_DERIVE, DOJO_PACKED_DERIVE, EXPECTED_DERIVE_ATTR_NAMES};
+use crate::derives::introspect::{self, utils::is_primitive_type};
 use crate::helpers::{
-    self, DiagnosticsExt, DojoChecker, DojoFormatter, DojoParser, DojoTokenizer, Member,
+    self, registry, DiagnosticsExt, DojoChecker, DojoFormatter, DojoParser, DojoTokenizer, Member,
     ProcMacroResultExt,
 };
 
@@ -21,7 +22,14 @@ pub struct DojoModel {
     keys_to_tuple: String,
     serialized_keys: Vec<String>,
     serialized_values: Vec<String>,
+    deserialized_keys: Vec<String>,
+    deserialized_values: Vec<String>,
+    model_member_inits: Vec<String>,
+    value_member_inits: Vec<String>,
+    key_size_checks: Vec<String>,
     unique_hash: String,
+    serde_impl: String,
+    default_impl: String,
 }
 
 impl DojoModel {
@@ -35,14 +43,23 @@ impl DojoModel {
             keys_to_tuple: String::default(),
             serialized_keys: vec![],
             serialized_values: vec![],
+            deserialized_keys: vec![],
+            deserialized_values: vec![],
+            model_member_inits: vec![],
+            value_member_inits: vec![],
+            key_size_checks: vec![],
             unique_hash: String::default(),
+            serde_impl: String::default(),
+            default_impl: String::default(),
         }
     }
     pub fn process(token_stream: TokenStream) -> ProcMacroResult {
         let db = SimpleParserDatabase::default();
 
         if let Some(struct_ast) = DojoParser::parse_and_find_struct(&db, &token_stream) {
-            return DojoModel::process_ast(&db, &struct_ast);
+            return introspect::with_item_origin(&token_stream, || {
+                DojoModel::process_ast(&db, &struct_ast)
+            });
         }
 
         ProcMacroResult::fail("'dojo::model' must be used on struct only.".to_string())
@@ -62,6 +79,17 @@ impl DojoModel {
             return failure;
         }
 
+        registry::register_name(&model.model_type);
+
+        if let Some(failure) = DojoChecker::is_not_generic(
+            db,
+            "model",
+            &model.model_type,
+            &struct_ast.generic_params(db),
+        ) {
+            return failure;
+        }
+
         let mut values: Vec<Member> = vec![];
         let mut keys: Vec<Member> = vec![];
         let mut key_types: Vec<String> = vec![];
@@ -79,18 +107,102 @@ impl DojoModel {
         );
 
         members.iter().for_each(|member| {
+            // skipped members are not stored, so they are neither keys nor values.
+            if member.skip {
+                if member.key {
+                    model.diagnostics.push_error(format!(
+                        "The key member '{}' cannot be skipped.",
+                        member.name
+                    ));
+                }
+
+                model.model_member_inits.push(format!(
+                    "{}: {},\n",
+                    member.name,
+                    Self::member_default_value(member)
+                ));
+                return;
+            }
+
+            model
+                .model_member_inits
+                .push(format!("{}: __{},\n", member.name, member.name));
+
             if member.key {
+                if member.default.is_some() {
+                    model.diagnostics.push_error(format!(
+                        "The key member '{}' cannot have a default value.",
+                        member.name
+                    ));
+                }
+
+                if member.bits.is_some() {
+                    model.diagnostics.push_error(format!(
+                        "The key member '{}' cannot have a bit width, as keys are not packed.",
+                        member.name
+                    ));
+                }
+
+                DojoChecker::check_key_type(&mut model.diagnostics, &member.name, &member.ty);
+
+                for key_type in DojoChecker::get_unchecked_key_types(&member.ty) {
+                    model
+                        .key_size_checks
+                        .push(DojoFormatter::check_key_size(member, &key_type));
+                }
+
                 keys.push(member.clone());
                 key_types.push(member.ty.clone());
-                key_attrs.push(format!("*self.{}", member.name.clone()));
+
+                // custom types (structs, enums) used as keys are not necessarily `Copy`.
+                key_attrs.push(if is_primitive_type(&member.ty) {
+                    format!("*self.{}", member.name)
+                } else {
+                    format!("core::clone::Clone::clone(self.{})", member.name)
+                });
                 model
                     .serialized_keys
                     .push(DojoFormatter::serialize_member_ty(member, true));
+                model
+                    .deserialized_keys
+                    .push(DojoFormatter::deserialize_member_ty(member, "keys"));
             } else {
                 values.push(member.clone());
+
+                if member.bits.is_some() {
+                    model
+                        .serialized_values
+                        .push(DojoFormatter::member_bits_offset(member));
+                }
+
+                // values are serialized as stored in the world storage, with the
+                // data of packed enums padded up to the size of their largest variant.
+                if introspect::may_contain_padded_enum(&member.ty) {
+                    model
+                        .serialized_values
+                        .push(DojoFormatter::serialize_padded_member_ty(member));
+                    model
+                        .deserialized_values
+                        .push(DojoFormatter::deserialize_padded_member_ty(
+                            member, "values",
+                        ));
+                } else {
+                    model
+                        .serialized_values
+                        .push(DojoFormatter::serialize_member_ty(member, true));
+                    model
+                        .deserialized_values
+                        .push(DojoFormatter::deserialize_member_ty(member, "values"));
+                }
                 model
-                    .serialized_values
-                    .push(DojoFormatter::serialize_member_ty(member, true));
+                    .value_member_inits
+                    .push(format!("{}: __{},\n", member.name, member.name));
+
+                if let Some(bits) = member.bits {
+                    model
+                        .serialized_values
+                        .push(DojoFormatter::check_member_bits(member, bits));
+                }
                 model
                     .members_values
                     .push(DojoFormatter::get_member_declaration(
@@ -119,12 +231,6 @@ impl DojoModel {
             }
         });
 
-        if keys.is_empty() {
-            model
-                .diagnostics
-                .push_error("Model must define at least one #[key] attribute".into());
-        }
-
         if values.is_empty() {
             model
                 .diagnostics
@@ -135,15 +241,17 @@ impl DojoModel {
             return ProcMacroResult::fail_with_diagnostics(model.diagnostics);
         }
 
-        (model.keys_to_tuple, model.key_type) = if keys.len() > 1 {
+        // A model without keys is a singleton, stored under a fixed entity id
+        // computed from its empty serialized keys (see `dojo::utils::singleton_entity_id`).
+        (model.keys_to_tuple, model.key_type) = if keys.len() == 1 {
             (
-                format!("({})", key_attrs.join(", ")),
-                format!("({})", key_types.join(", ")),
+                key_attrs.first().unwrap().to_string(),
+                key_types.first().unwrap().to_string(),
             )
         } else {
             (
-                key_attrs.first().unwrap().to_string(),
-                key_types.first().unwrap().to_string(),
+                format!("({})", key_attrs.join(", ")),
+                format!("({})", key_types.join(", ")),
             )
         };
 
@@ -160,26 +268,50 @@ impl DojoModel {
             .filter(|d| d != DOJO_INTROSPECT_DERIVE && d != DOJO_PACKED_DERIVE)
             .collect::<Vec<String>>();
 
-        let mut missing_derive_attr_names = vec![];
+        // As skipped members are not stored, the model cannot use a derived `Serde`
+        // implementation and a dedicated one is generated.
+        let has_skipped_members = members.iter().any(|m| m.skip);
 
-        // If Introspect or IntrospectPacked derive attribute is not set for the model,
-        // use Introspect by default.
-        if derive_attr_names.contains(&DOJO_PACKED_DERIVE.to_string()) {
-            missing_derive_attr_names.push(DOJO_PACKED_DERIVE.to_string());
-        } else {
-            missing_derive_attr_names.push(DOJO_INTROSPECT_DERIVE.to_string());
-        }
+        DojoChecker::check_skipped_members_serde(
+            db,
+            &mut model.diagnostics,
+            &model.model_type,
+            struct_ast.attributes(db).query_attr(db, "derive"),
+            &struct_ast.members(db).elements(db),
+        );
+
+        let mut missing_derive_attr_names = vec![];
 
         // Add missing expected derive attributes for "Model" struct.
         EXPECTED_DERIVE_ATTR_NAMES.iter().for_each(|expected_attr| {
             let attr = expected_attr.to_string();
 
             if !derive_attr_names.contains(&attr) {
-                missing_derive_attr_names.push(attr.clone());
+                if !(has_skipped_members && attr == "Serde") {
+                    missing_derive_attr_names.push(attr.clone());
+                }
                 model.model_value_derive_attr_names.push(attr);
             }
         });
 
+        if has_skipped_members {
+            model.serde_impl = model.generate_serde_impl(&members);
+        }
+
+        // Default values declared with `#[default(...)]` are used to generate
+        // `Default` implementations for the model and the model value.
+        if members.iter().any(|m| m.default.is_some()) {
+            if derive_attr_names.contains(&"Default".to_string()) {
+                model.diagnostics.push_error(format!(
+                    "The model '{}' has members with default values and cannot derive Default, \
+                    as a dedicated Default implementation is generated.",
+                    model.model_type
+                ));
+            }
+
+            model.default_impl = model.generate_default_impl(&members);
+        }
+
         let is_packed = derive_attr_names.contains(&DOJO_PACKED_DERIVE.to_string());
 
         model.unique_hash = helpers::compute_unique_hash(
@@ -192,6 +324,24 @@ impl DojoModel {
 
         let model_code = model.generate_model_code();
 
+        // If IntrospectPacked derive attribute is not set for the model, use Introspect
+        // by default. The introspection is generated from the original struct, as the
+        // re-emitted one does not have the `#[skip]` and `#[bits(N)]` member attributes.
+        let introspect = introspect::process_model(db, struct_ast, is_packed);
+
+        // member attributes are checked by both the model and its introspection.
+        for diagnostic in introspect.diagnostics {
+            if !model
+                .diagnostics
+                .iter()
+                .any(|d| d.message == diagnostic.message)
+            {
+                model.diagnostics.push(diagnostic);
+            }
+        }
+
+        let introspect = introspect.token_stream;
+
         let original_struct = DojoTokenizer::rebuild_original_struct(db, struct_ast);
 
         let missing_derive_attr = if missing_derive_attr_names.is_empty() {
@@ -209,6 +359,9 @@ impl DojoModel {
                 #missing_derive_attr
                 #original_struct
 
+                // introspection
+                #introspect
+
                 // model
                 #model_code
             },
@@ -216,6 +369,104 @@ impl DojoModel {
         )
     }
 
+    /// Generate a `Serde` implementation for a model with skipped members.
+    /// Skipped members are not serialized and are restored to their default
+    /// value on deserialization.
+    fn generate_serde_impl(&self, members: &[Member]) -> String {
+        let model_type = &self.model_type;
+
+        let serialized_members = members
+            .iter()
+            .filter(|m| !m.skip)
+            .map(|m| DojoFormatter::serialize_member_ty(m, true))
+            .collect::<Vec<_>>()
+            .join("");
+
+        let deserialized_members = members
+            .iter()
+            .filter(|m| !m.skip)
+            .map(|m| {
+                format!(
+                    "let {} = core::serde::Serde::deserialize(ref serialized)?;\n",
+                    m.name
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("");
+
+        let member_inits = members
+            .iter()
+            .map(|m| {
+                if m.skip {
+                    format!("{}: {},\n", m.name, Self::member_default_value(m))
+                } else {
+                    format!("{},\n", m.name)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("");
+
+        format!(
+            "impl {model_type}Serde of core::serde::Serde<{model_type}> {{
+    fn serialize(self: @{model_type}, ref serialized: Array<felt252>) {{
+        {serialized_members}
+    }}
+
+    fn deserialize(ref serialized: Span<felt252>) -> Option<{model_type}> {{
+        {deserialized_members}
+        Option::Some({model_type} {{
+            {member_inits}
+        }})
+    }}
+}}"
+        )
+    }
+
+    /// Generate `Default` implementations for a model and its model value,
+    /// using the values declared with `#[default(...)]` or `Default::default()`
+    /// for members without declared default value.
+    fn generate_default_impl(&self, members: &[Member]) -> String {
+        let model_type = &self.model_type;
+
+        let model_inits = members
+            .iter()
+            .map(|m| format!("{}: {},\n", m.name, Self::member_default_value(m)))
+            .collect::<Vec<_>>()
+            .join("");
+
+        let value_inits = members
+            .iter()
+            .filter(|m| !m.key && !m.skip)
+            .map(|m| format!("{}: {},\n", m.name, Self::member_default_value(m)))
+            .collect::<Vec<_>>()
+            .join("");
+
+        format!(
+            "impl {model_type}Default of core::traits::Default<{model_type}> {{
+    fn default() -> {model_type} {{
+        {model_type} {{
+            {model_inits}
+        }}
+    }}
+}}
+
+impl {model_type}ValueDefault of core::traits::Default<{model_type}Value> {{
+    fn default() -> {model_type}Value {{
+        {model_type}Value {{
+            {value_inits}
+        }}
+    }}
+}}"
+        )
+    }
+
+    fn member_default_value(member: &Member) -> String {
+        member
+            .default
+            .clone()
+            .unwrap_or_else(|| "core::traits::Default::default()".to_string())
+    }
+
     fn generate_model_code(&self) -> TokenStream {
         let (
             model_type,
@@ -225,7 +476,13 @@ impl DojoModel {
             keys_to_tuple,
             serialized_keys,
             serialized_values,
+            deserialized_keys,
+            deserialized_values,
+            model_member_inits,
+            value_member_inits,
             unique_hash,
+            serde_impl,
+            default_impl,
         ) = (
             &self.model_type,
             format!(
@@ -237,9 +494,48 @@ impl DojoModel {
             &self.keys_to_tuple,
             self.serialized_keys.join(""),
             self.serialized_values.join(""),
+            self.deserialized_keys.join(""),
+            self.deserialized_values.join(""),
+            self.model_member_inits.join(""),
+            self.value_member_inits.join(""),
             &self.unique_hash,
+            &self.serde_impl,
+            &self.default_impl,
         );
 
+        // Outputs the serialized default values of the model, if some default values
+        // have been declared with `#[default(...)]`.
+        let default_values = if self.default_impl.is_empty() {
+            "[].span()".to_string()
+        } else {
+            format!(
+                "let value: super::{model_type}Value = core::traits::Default::default();
+            dojo::model::model_value::ModelValueParser::<super::{model_type}Value>::serialize_values(@value)"
+            )
+        };
+
+        // Custom key types whose size is unknown at expansion time are checked when
+        // the model contract is deployed. The checks are done outside of the contract
+        // module, where the key types are in scope.
+        let (key_size_checks, constructor) = if self.key_size_checks.is_empty() {
+            (String::default(), String::default())
+        } else {
+            (
+                format!(
+                    "fn {model_type}_check_key_sizes() {{
+    {}
+}}",
+                    self.key_size_checks.join("")
+                ),
+                format!(
+                    "#[constructor]
+    fn constructor(ref self: ContractState) {{
+        super::{model_type}_check_key_sizes();
+    }}"
+                ),
+            )
+        };
+
         let content = format!(
         "{model_value_derive_attr_names}
 pub struct {model_type}Value {{
@@ -255,9 +551,15 @@ pub impl {model_type}KeyParser of dojo::model::model::KeyParser<{model_type}, {m
     }}
 }}
 
+{serde_impl}
+
+{default_impl}
+
 impl {model_type}ModelValueKey of dojo::model::model_value::ModelValueKey<{model_type}Value, {model_type}KeyType> {{
 }}
 
+{key_size_checks}
+
 // Impl to get the static definition of a model
 pub mod m_{model_type}_definition {{
     use super::{model_type};
@@ -286,6 +588,10 @@ pub mod m_{model_type}_definition {{
         fn size() -> Option<usize> {{
             dojo::meta::Introspect::<{model_type}>::size()
         }}
+
+        fn default_values() -> Span<felt252> {{
+            {default_values}
+        }}
     }}
 }}
 
@@ -303,6 +609,13 @@ pub impl {model_type}ModelParser of dojo::model::model::ModelParser<{model_type}
         {serialized_values}
         core::array::ArrayTrait::span(@serialized)
     }}
+    fn deserialize(ref keys: Span<felt252>, ref values: Span<felt252>) -> Option<{model_type}> {{
+        {deserialized_keys}
+        {deserialized_values}
+        Option::Some({model_type} {{
+            {model_member_inits}
+        }})
+    }}
 }}
 
 pub impl {model_type}ModelValueParser of dojo::model::model_value::ModelValueParser<{model_type}Value> {{
@@ -311,6 +624,12 @@ pub impl {model_type}ModelValueParser of dojo::model::model_value::ModelValuePar
         {serialized_values}
         core::array::ArrayTrait::span(@serialized)
     }}
+    fn deserialize(ref values: Span<felt252>) -> Option<{model_type}Value> {{
+        {deserialized_values}
+        Option::Some({model_type}Value {{
+            {value_member_inits}
+        }})
+    }}
 }}
 
 pub impl {model_type}ModelImpl = dojo::model::model::ModelImpl<{model_type}>;
@@ -324,6 +643,8 @@ pub mod m_{model_type} {{
     #[storage]
     struct Storage {{}}
 
+    {constructor}
+
     #[abi(embed_v0)]
     impl {model_type}__DojoDeployedModelImpl = dojo::model::component::IDeployedModelImpl<ContractState, {model_type}>;
 
@@ -363,3 +684,345 @@ pub mod m_{model_type} {{
         TokenStream::new(vec![DojoTokenizer::tokenize(&content)])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_lang_macro::Severity;
+
+    #[test]
+    fn test_generic_model_is_rejected() {
+        let input = "
+        struct Position<T> {
+            #[key]
+            player: ContractAddress,
+            x: T,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+
+        assert_eq!(res.diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The model 'Position' cannot have generic parameters. Declare a dedicated model \
+            with concrete member types for each instantiation instead \
+            (e.g. 'PositionU32', 'PositionFelt')."
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_skipped_members() {
+        let input = "
+        struct Position {
+            #[key]
+            player: ContractAddress,
+            x: u32,
+            #[skip]
+            distance: u32,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("impl PositionSerde of core::serde::Serde<Position>"));
+        assert!(output.contains("distance: core::traits::Default::default()"));
+        assert!(!output.contains("pub distance: u32"));
+        assert!(!output.contains("#[skip]"));
+
+        // skipped key
+        let input = "
+        struct Position {
+            #[key]
+            #[skip]
+            player: ContractAddress,
+            x: u32,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The key member 'player' cannot be skipped.".to_string()
+        );
+
+        // derived Serde
+        let input = "
+        #[derive(Serde)]
+        struct Position {
+            #[key]
+            player: ContractAddress,
+            x: u32,
+            #[skip]
+            distance: u32,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "'Position' has skipped members and cannot derive Serde, as skipped members \
+            are not part of its layout."
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_default_values() {
+        let input = "
+        struct Position {
+            #[key]
+            player: ContractAddress,
+            #[default(10)]
+            x: u32,
+            y: u32,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("impl PositionDefault of core::traits::Default<Position>"));
+        assert!(
+            output.contains("impl PositionValueDefault of core::traits::Default<PositionValue>")
+        );
+        assert!(output.contains("x: 10,"));
+        assert!(output.contains("fn default_values() -> Span<felt252>"));
+        assert!(output.contains("core::serde::Serde::<u32>::serialize(@(10), ref serialized);"));
+        assert!(output.contains("attrs.append('default');"));
+        // primitive keys don't need any deployment check.
+        assert!(!output.contains("#[constructor]"));
+
+        // default value on a key
+        let input = "
+        struct Position {
+            #[key]
+            #[default(1)]
+            player: u32,
+            x: u32,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The key member 'player' cannot have a default value.".to_string()
+        );
+
+        // derived Default
+        let input = "
+        #[derive(Default)]
+        struct Position {
+            #[key]
+            player: u32,
+            #[default(10)]
+            x: u32,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The model 'Position' has members with default values and cannot derive Default, \
+            as a dedicated Default implementation is generated."
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_values_are_padded_in_storage() {
+        let input = "
+        struct Fighter {
+            #[key]
+            id: u32,
+            state: State,
+            level: u8,
+            items: Array<(u8, u16)>,
+            #[skip]
+            cached: u8,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("core::serde::Serde::serialize(self.id, ref serialized);"));
+        assert!(output
+            .contains("dojo::storage::padding::serialize_padded(self.state, ref serialized);"));
+        assert!(output.contains("let __id = core::serde::Serde::<u32>::deserialize(ref keys)?;"));
+        assert!(output.contains(
+            "let __state = dojo::storage::padding::deserialize_padded::<State>(ref values)?;"
+        ));
+        assert!(output.contains("cached: core::traits::Default::default(),"));
+
+        // types which can't contain a packed enum are not padded.
+        assert!(output.contains("core::serde::Serde::serialize(self.level, ref serialized);"));
+        assert!(output.contains("core::serde::Serde::serialize(self.items, ref serialized);"));
+        assert!(
+            output.contains("let __level = core::serde::Serde::<u8>::deserialize(ref values)?;")
+        );
+    }
+
+    #[test]
+    fn test_bits_members() {
+        let input = "
+        #[derive(IntrospectPacked)]
+        struct Player {
+            #[key]
+            player: u32,
+            #[bits(4)]
+            level: u8,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("dojo::storage::packing::pow2_const(4)"));
+        assert!(output.contains("The value of the member `level` exceeds 4 bits."));
+        assert!(!output.contains("#[bits(4)]"));
+
+        // bit width on a key
+        let input = "
+        struct Player {
+            #[key]
+            #[bits(4)]
+            player: u32,
+            level: u8,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The key member 'player' cannot have a bit width, as keys are not packed.".to_string()
+        );
+
+        // invalid bit width
+        let input = "
+        #[derive(IntrospectPacked)]
+        struct Player {
+            #[key]
+            player: u32,
+            #[bits(0)]
+            level: u8,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The bits attribute of the member 'level' expects exactly one bit width between 1 \
+            and 128 (i.e #[bits(4)])."
+                .to_string()
+        );
+
+        // the variant selector of an enum is narrowed and range checked
+        let input = "
+        #[derive(IntrospectPacked)]
+        struct Player {
+            #[key]
+            player: u32,
+            #[bits(2)]
+            state: State,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("dojo::meta::layout::narrow_enum_layout::<State>(2)"));
+        assert!(output.contains("let __state_offset = core::array::ArrayTrait::len(@serialized);"));
+        assert!(output.contains("The value of the member `state` exceeds 2 bits."));
+
+        // only unsigned integers and enums can be narrowed
+        let input = "
+        #[derive(IntrospectPacked)]
+        struct Player {
+            #[key]
+            player: u32,
+            #[bits(4)]
+            position: (u8, u8),
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The bits attribute of the member 'position' can only be used on unsigned \
+            integers (u8 to u128) and enums."
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_singleton_model() {
+        let input = "
+        struct Config {
+            fee: u32,
+            paused: bool,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("type ConfigKeyType = ();"));
+    }
+
+    #[test]
+    fn test_key_types() {
+        let input = "
+        struct Tile {
+            #[key]
+            coord: Coordinate,
+            #[key]
+            layer: u8,
+            kind: u8,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("(core::clone::Clone::clone(self.coord), *self.layer)"));
+
+        // the size of `Coordinate` is unknown at expansion time, so it's checked on deployment.
+        assert!(output.contains("fn Tile_check_key_sizes()"));
+        assert!(output.contains("dojo::meta::Introspect::<Coordinate>::size()"));
+        assert!(output.contains("#[constructor]"));
+        assert!(!output.contains("Introspect::<u8>::size()"));
+
+        let input = "
+        struct Message {
+            #[key]
+            channel: ByteArray,
+            #[key]
+            ids: (u32, Span<u32>),
+            content: felt252,
+        }";
+
+        let res = DojoModel::process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 2);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "The member 'channel' cannot be used as a key: 'ByteArray' is a string with a \
+            dynamic size. Keys must have a fixed size, like primitive types, tuples, or structs \
+            and enums with a fixed layout."
+        );
+        assert!(res.diagnostics[1]
+            .message
+            .starts_with("The member 'ids' cannot be used as a key: 'Span<u32>' is an array"));
+    }
+}
@@ -3,7 +3,22 @@ pub const DOJO_PACKED_DERIVE: &str = "IntrospectPacked";
 
 pub const EXPECTED_DERIVE_ATTR_NAMES: [&str; 2] = ["Serde", "Drop"];
 
+/// Member attributes handled by the Dojo macros only.
+pub const DOJO_MEMBER_ATTRS: [&str; 2] = ["skip", "bits"];
+
 pub const CAIRO_DELIMITERS: [char; 7] = ['[', ']', '<', '>', '(', ')', ','];
 
+// the widest type which can be narrowed with `#[bits(N)]` is u128.
+pub const MAX_MEMBER_BITS: u8 = 128;
+// primitive types whose packed bit width can be narrowed with `#[bits(N)]`.
+pub const NARROWABLE_TYPES: [&str; 5] = ["u8", "u16", "u32", "u64", "u128"];
+
 pub const CONSTRUCTOR_FN: &str = "constructor";
 pub const DOJO_INIT_FN: &str = "dojo_init";
+// storage variable flagging an initialized contract, for `owner_can_init` contracts.
+pub const DOJO_INIT_FLAG_STORAGE: &str = "__dojo_initialized__";
+
+pub const DOJO_STATIC_LAYOUTS_ENV: &str = "DOJO_STATIC_LAYOUTS";
+pub const DOJO_CHECK_TAGS_ENV: &str = "DOJO_CHECK_TAGS";
+
+pub const DOJO_MANIFEST_FILE: &str = "dojo_macros_manifest.json";
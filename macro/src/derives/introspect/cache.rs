@@ -0,0 +1,401 @@
+#[cfg(test)]
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use cairo_lang_macro::{TokenStream, TokenTree};
+
+use crate::constants::DOJO_STATIC_LAYOUTS_ENV;
+
+/// Introspection data of a type which are fully known at expansion time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StaticIntrospection {
+    /// Constant Cairo expression of the type layout.
+    pub layout: String,
+    /// Size of the type, `None` if the size is dynamic.
+    pub size: Option<u32>,
+    /// Bit widths of the type items, for packed types only.
+    pub packed_layout: Option<Vec<String>>,
+    /// True if the type contains a packed enum with variants of different sizes,
+    /// whose data are padded in the world storage.
+    pub padded: bool,
+}
+
+/// The origin of the item being expanded: the file it is defined in, which identifies
+/// both its crate and its module, and the offset of its definition in this file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemOrigin {
+    pub file: String,
+    pub offset: u32,
+}
+
+impl ItemOrigin {
+    /// Returns the origin of the item of a token stream, if it comes from a file.
+    pub fn from_token_stream(token_stream: &TokenStream) -> Option<Self> {
+        let file = token_stream.metadata().original_file_path.clone()?;
+        let offset = token_stream.tokens.first().and_then(|token| match token {
+            TokenTree::Ident(token) => Some(token.span.start),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        })?;
+
+        Some(Self { file, offset })
+    }
+}
+
+/// The key of a cache entry: a type name, in the file it is defined in.
+type TypeKey = (String, String);
+
+/// The introspection data registered for a type key, by definition offset.
+/// Several definitions of the same type name may exist in a file (i.e in inline
+/// modules), and a definition may have been moved by an edit of the file.
+type CacheEntry = HashMap<u32, StaticIntrospection>;
+
+/// Cache shared by all the Introspect derive invocations done by the macro server,
+/// so a type can reuse the introspection data of sibling types already expanded
+/// instead of calling `Introspect::<T>::layout()` and `Introspect::<T>::size()`
+/// at runtime.
+///
+/// The cache is only enabled when the DOJO_STATIC_LAYOUTS environment variable is set.
+/// Types are identified by their name and the file they are defined in, and a type
+/// can only reuse the data of the types defined in the same file. As the items of a
+/// file are expanded in order, the result of an expansion doesn't depend on the order
+/// in which the files are expanded: a type defined above in the same file is always
+/// known, and any other type is introspected at runtime.
+///
+/// Expanding the same definition again (i.e from the language server) replaces its
+/// data, but whenever several definitions are known for a name, this name is not
+/// resolved at expansion time and the `Introspect` implementation is used instead.
+fn cache() -> &'static Mutex<HashMap<TypeKey, CacheEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<TypeKey, CacheEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+thread_local! {
+    // The origin of the item being expanded by the current thread.
+    static CURRENT_ORIGIN: RefCell<Option<ItemOrigin>> = const { RefCell::new(None) };
+}
+
+#[cfg(test)]
+thread_local! {
+    // Enable the cache for the current test thread only, without touching
+    // the environment shared by all the tests.
+    static ENABLED_FOR_TEST: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn is_enabled() -> bool {
+    #[cfg(test)]
+    if ENABLED_FOR_TEST.with(|enabled| enabled.get()) {
+        return true;
+    }
+
+    std::env::var(DOJO_STATIC_LAYOUTS_ENV).is_ok()
+}
+
+/// Run an expansion with the origin of the expanded item, used to register and
+/// to look up the introspection data of the types of its file.
+pub fn with_origin<R>(origin: Option<ItemOrigin>, expand: impl FnOnce() -> R) -> R {
+    let previous = CURRENT_ORIGIN.with(|current| current.replace(origin));
+    let result = expand();
+    CURRENT_ORIGIN.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+fn current_origin() -> Option<ItemOrigin> {
+    CURRENT_ORIGIN.with(|current| current.borrow().clone())
+}
+
+/// Register the introspection data generated for a type, if they are fully static.
+/// Generic types are never registered as their data depend on their instantiation,
+/// nor are types whose origin is unknown.
+pub fn register(
+    name: &str,
+    generic_types: &[String],
+    layout: &str,
+    size: &str,
+    packed_layout: Option<Vec<String>>,
+    padded: bool,
+) {
+    if !is_enabled() || !generic_types.is_empty() {
+        return;
+    }
+
+    let Some(origin) = current_origin() else {
+        return;
+    };
+
+    if let Some(introspection) = build_static_introspection(layout, size, packed_layout, padded) {
+        if let Ok(mut cache) = cache().lock() {
+            insert(&mut cache, &origin, name, introspection);
+        }
+    }
+}
+
+/// Get the static introspection data of a type defined in the file of the item
+/// being expanded, if known.
+pub fn get(name: &str) -> Option<StaticIntrospection> {
+    if !is_enabled() {
+        return None;
+    }
+
+    let origin = current_origin()?;
+    cache()
+        .lock()
+        .ok()
+        .and_then(|cache| lookup(&cache, &origin.file, name))
+}
+
+fn build_static_introspection(
+    layout: &str,
+    size: &str,
+    packed_layout: Option<Vec<String>>,
+    padded: bool,
+) -> Option<StaticIntrospection> {
+    if !is_static_layout(layout) {
+        return None;
+    }
+
+    let size = parse_static_size(size)?;
+
+    Some(StaticIntrospection {
+        layout: layout.to_string(),
+        size,
+        packed_layout,
+        padded,
+    })
+}
+
+/// A layout is static if it does not rely on another introspect implementation
+/// and does not have to be merged at runtime.
+fn is_static_layout(layout: &str) -> bool {
+    !layout.is_empty()
+        && !layout.contains("dojo::meta::introspect::Introspect::")
+        && !layout.contains("merged_layout")
+}
+
+/// Parse a generated size function body, if it is a constant.
+/// Returns `Some(None)` for a dynamic size.
+fn parse_static_size(size: &str) -> Option<Option<u32>> {
+    let size = size.trim();
+
+    if size == "Option::None" {
+        return Some(None);
+    }
+
+    size.strip_prefix("Option::Some(")
+        .and_then(|s| s.strip_suffix(')'))
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .map(Some)
+}
+
+fn insert(
+    cache: &mut HashMap<TypeKey, CacheEntry>,
+    origin: &ItemOrigin,
+    name: &str,
+    introspection: StaticIntrospection,
+) {
+    cache
+        .entry((origin.file.clone(), name.to_string()))
+        .or_default()
+        .insert(origin.offset, introspection);
+}
+
+fn lookup(
+    cache: &HashMap<TypeKey, CacheEntry>,
+    file: &str,
+    name: &str,
+) -> Option<StaticIntrospection> {
+    let definitions = cache.get(&(file.to_string(), name.to_string()))?;
+
+    match definitions.values().collect::<Vec<_>>()[..] {
+        [introspection] => Some(introspection.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_macro::{ProcMacroResult, TokenStream};
+    use cairo_lang_parser::utils::SimpleParserDatabase;
+
+    use super::super::structs::DojoStructIntrospect;
+    use super::*;
+    use crate::helpers::{DojoParser, DojoTokenizer};
+
+    #[test]
+    fn test_build_static_introspection() {
+        let layout = "dojo::meta::Layout::Fixed(array![8,32].span())";
+
+        assert_eq!(
+            build_static_introspection(layout, "Option::Some(2)", Some(vec!["8".into()]), false),
+            Some(StaticIntrospection {
+                layout: layout.to_string(),
+                size: Some(2),
+                packed_layout: Some(vec!["8".into()]),
+                padded: false,
+            })
+        );
+
+        assert_eq!(
+            build_static_introspection(layout, "Option::None", None, false).map(|i| i.size),
+            Some(None)
+        );
+
+        // runtime size computation
+        assert!(build_static_introspection(
+            layout,
+            "let sizes : Array<Option<usize>> = array![]; Option::Some(dojo::utils::sum(sizes))",
+            None,
+            false
+        )
+        .is_none());
+
+        // nested custom type
+        assert!(build_static_introspection(
+            "dojo::meta::introspect::Introspect::<Vec2>::layout()",
+            "Option::Some(2)",
+            None,
+            false
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_registrations() {
+        let mut cache = HashMap::new();
+        let introspection = StaticIntrospection {
+            layout: "dojo::meta::Layout::Fixed(array![8].span())".to_string(),
+            size: Some(1),
+            packed_layout: Some(vec!["8".into()]),
+            padded: false,
+        };
+        let origin = |file: &str, offset| ItemOrigin {
+            file: file.to_string(),
+            offset,
+        };
+
+        insert(
+            &mut cache,
+            &origin("a.cairo", 10),
+            "Direction",
+            introspection.clone(),
+        );
+        assert_eq!(
+            lookup(&cache, "a.cairo", "Direction"),
+            Some(introspection.clone())
+        );
+
+        // only the types of the same file are known.
+        assert_eq!(lookup(&cache, "b.cairo", "Direction"), None);
+
+        // expanding the same definition again replaces its data.
+        let updated = StaticIntrospection {
+            size: Some(2),
+            ..introspection.clone()
+        };
+        insert(
+            &mut cache,
+            &origin("a.cairo", 10),
+            "Direction",
+            updated.clone(),
+        );
+        assert_eq!(lookup(&cache, "a.cairo", "Direction"), Some(updated));
+
+        // another definition with the same name in another file.
+        insert(
+            &mut cache,
+            &origin("b.cairo", 10),
+            "Direction",
+            introspection.clone(),
+        );
+        assert_eq!(
+            lookup(&cache, "b.cairo", "Direction"),
+            Some(introspection.clone())
+        );
+
+        // another definition with the same name in the same file.
+        insert(
+            &mut cache,
+            &origin("a.cairo", 50),
+            "Direction",
+            introspection,
+        );
+        assert_eq!(lookup(&cache, "a.cairo", "Direction"), None);
+    }
+
+    fn origin(offset: u32) -> Option<ItemOrigin> {
+        Some(ItemOrigin {
+            file: "cache_test.cairo".to_string(),
+            offset,
+        })
+    }
+
+    fn derive(input: &str, offset: u32) -> ProcMacroResult {
+        let db = SimpleParserDatabase::default();
+        let struct_ast = DojoParser::parse_and_find_struct(
+            &db,
+            &TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+        .unwrap();
+
+        with_origin(origin(offset), || {
+            DojoStructIntrospect::process(&db, &struct_ast, false)
+        })
+    }
+
+    #[test]
+    fn test_static_layout_substitution() {
+        ENABLED_FOR_TEST.with(|enabled| enabled.set(true));
+
+        let vec2 = "
+            #[derive(Introspect, Drop, Serde)]
+            struct CacheTestVec2 {
+                x: u32,
+                y: u32,
+            }";
+        let position = "
+            #[derive(Introspect, Drop, Serde)]
+            struct CacheTestPosition {
+                id: u32,
+                v: CacheTestVec2,
+            }";
+
+        assert!(derive(vec2, 0).diagnostics.is_empty());
+
+        let res = derive(position, 100);
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(!output.contains("Introspect::<CacheTestVec2>"));
+        assert!(output.contains("Option::Some(3)"));
+
+        // the same definition expanded again, after an edit
+        let res = derive(
+            "
+            #[derive(Introspect, Drop, Serde)]
+            struct CacheTestVec2 {
+                x: u32,
+                y: u32,
+                z: u32,
+            }",
+            0,
+        );
+        assert!(res.diagnostics.is_empty());
+
+        let output = derive(position, 100).token_stream.to_string();
+        assert!(output.contains("Option::Some(4)"));
+
+        // another definition with the same name in the same file
+        assert!(derive(vec2, 200).diagnostics.is_empty());
+        assert!(with_origin(origin(300), || get("CacheTestVec2")).is_none());
+
+        let output = derive(position, 100).token_stream.to_string();
+        assert!(output.contains("Introspect::<CacheTestVec2>"));
+
+        // no origin
+        assert!(get("CacheTestVec2").is_none());
+
+        ENABLED_FOR_TEST.with(|enabled| enabled.set(false));
+    }
+}
@@ -2,8 +2,9 @@ use cairo_lang_macro::{Diagnostic, ProcMacroResult, TokenStream};
 use cairo_lang_parser::utils::SimpleParserDatabase;
 use cairo_lang_syntax::node::ast::{ItemEnum, OptionTypeClause, Variant};
 use cairo_lang_syntax::node::helpers::QueryAttrs;
-use cairo_lang_syntax::node::Terminal;
+use cairo_lang_syntax::node::{Terminal, TypedSyntaxNode};
 
+use crate::constants::DOJO_STATIC_LAYOUTS_ENV;
 use crate::helpers::{DiagnosticsExt, DojoChecker, ProcMacroResultExt};
 
 #[derive(Debug)]
@@ -44,34 +45,114 @@ impl DojoEnumIntrospect {
         let enum_name = enum_ast.name(db).text(db).into();
         let variant_sizes = self.compute_enum_variant_sizes(db, enum_ast);
 
-        let layout = if is_packed {
+        // packed variants with different sizes, as bit widths of their serialized data.
+        let mut padded_variants = None;
+
+        let (layout, packed_layout) = if is_packed {
             if self.is_enum_packable(&variant_sizes) {
-                self.build_packed_enum_layout(db, enum_ast)
+                let layouts = self.build_packed_enum_layouts(db, enum_ast);
+                (super::layout::build_packed_layout(&layouts), Some(layouts))
+            } else if let Some(variant_widths) = self.compute_variant_bit_widths(db, enum_ast) {
+                let layouts = build_padded_enum_layouts(&variant_widths);
+                padded_variants = Some(variant_widths);
+                (super::layout::build_packed_layout(&layouts), Some(layouts))
             } else {
-                self.diagnostics.push_error(
-                    "To be packed, all variants must have fixed layout of same size.".to_string(),
-                );
-                "".to_string()
+                self.diagnostics.push_error(format!(
+                    "To be packed, all variants must have a fixed layout. Variants of \
+                    different sizes must only contain types with a layout known at compile \
+                    time: primitives and tuples of them, or packed types defined above in \
+                    the same file when the {DOJO_STATIC_LAYOUTS_ENV} environment variable \
+                    is set."
+                ));
+                ("".to_string(), None)
             }
         } else {
-            format!(
+            let layout = format!(
                 "dojo::meta::Layout::Enum(
                 array![
                 {}
                 ].span()
             )",
                 self.build_variant_layouts(db, enum_ast)
-            )
+            );
+            (layout, None)
         };
 
-        let (gen_types, gen_impls) =
-            super::generics::build_generic_types_and_impls(db, enum_ast.generic_params(db));
-        let enum_size = self.compute_enum_layout_size(&variant_sizes, is_packed);
+        let variant_types = enum_ast
+            .variants(db)
+            .elements(db)
+            .iter()
+            .filter_map(|v| match v.type_clause(db) {
+                OptionTypeClause::Empty(_) => None,
+                OptionTypeClause::TypeClause(type_clause) => Some(
+                    type_clause
+                        .ty(db)
+                        .as_syntax_node()
+                        .get_text_without_trivia(db),
+                ),
+            })
+            .collect::<Vec<_>>();
+
+        let (gen_types, gen_impls) = super::generics::build_generic_types_and_impls(
+            db,
+            enum_ast.generic_params(db),
+            &variant_types,
+        );
+        let enum_size = match &padded_variants {
+            Some(variant_widths) => format!("Option::Some({})", padded_enum_size(variant_widths)),
+            None => self.compute_enum_layout_size(&variant_sizes, is_packed),
+        };
         let ty = self.build_enum_ty(db, &enum_name, enum_ast);
 
+        if self.diagnostics.is_empty() {
+            let padded = padded_variants.is_some()
+                || variant_types
+                    .iter()
+                    .any(|t| super::may_contain_padded_enum(t));
+
+            super::cache::register(
+                &enum_name,
+                &gen_types,
+                &layout,
+                &enum_size,
+                packed_layout,
+                padded,
+            );
+        }
+
+        // The data of the variants of a padded enum are only padded in the world
+        // storage (see `dojo::storage::padding`), so its `Serde` implementation is kept.
         super::generate_introspect(&enum_name, &enum_size, &gen_types, gen_impls, &layout, &ty)
     }
 
+    /// Compute the bit widths of the packed data of each variant, if they are all
+    /// known at compile time.
+    pub fn compute_variant_bit_widths(
+        &mut self,
+        db: &SimpleParserDatabase,
+        enum_ast: &ItemEnum,
+    ) -> Option<Vec<Vec<u8>>> {
+        enum_ast
+            .variants(db)
+            .elements(db)
+            .iter()
+            .map(|v| match v.type_clause(db) {
+                OptionTypeClause::Empty(_) => Some(vec![]),
+                OptionTypeClause::TypeClause(type_clause) => {
+                    super::layout::get_packed_field_layout_from_type_clause(
+                        db,
+                        &mut self.diagnostics,
+                        &type_clause,
+                    )
+                    .iter()
+                    .flat_map(|l| l.split(','))
+                    .map(|w| w.trim().parse::<u8>().ok())
+                    .collect::<Option<Vec<_>>>()
+                }
+            })
+            .collect::<Option<Vec<_>>>()
+    }
+
     pub fn compute_enum_variant_sizes(
         &self,
         db: &SimpleParserDatabase,
@@ -138,13 +219,13 @@ impl DojoEnumIntrospect {
     }
 
     //
-    pub fn build_packed_enum_layout(
+    pub fn build_packed_enum_layouts(
         &mut self,
         db: &SimpleParserDatabase,
         enum_ast: &ItemEnum,
-    ) -> String {
+    ) -> Vec<String> {
         // to be packable, all variants data must have the same size.
-        // as this point has already been checked before calling `build_packed_enum_layout`,
+        // as this point has already been checked before calling `build_packed_enum_layouts`,
         // just use the first variant to generate the fixed layout.
         let elements = enum_ast.variants(db).elements(db);
         let mut variant_layout = if elements.is_empty() {
@@ -164,22 +245,7 @@ impl DojoEnumIntrospect {
 
         // don't forget the store the variant value
         variant_layout.insert(0, "8".to_string());
-
-        if variant_layout
-            .iter()
-            .any(|v| super::layout::is_custom_layout(v.as_str()))
-        {
-            super::layout::generate_cairo_code_for_fixed_layout_with_custom_types(&variant_layout)
-        } else {
-            format!(
-                "dojo::meta::Layout::Fixed(
-                array![
-                {}
-                ].span()
-            )",
-                variant_layout.join(",")
-            )
-        }
+        variant_layout
     }
 
     /// build the full layout for every variant in the Enum.
@@ -265,3 +331,77 @@ impl DojoEnumIntrospect {
         }
     }
 }
+
+/// Build the packed layout of an enum with variants of different sizes: the variant
+/// selector followed by, for each serialized data item, the largest bit width used
+/// by a variant at this position.
+fn build_padded_enum_layouts(variant_widths: &[Vec<u8>]) -> Vec<String> {
+    let data_size = padded_enum_size(variant_widths) - 1;
+    let mut layouts = vec!["8".to_string()];
+
+    for i in 0..data_size {
+        let width = variant_widths
+            .iter()
+            .filter_map(|widths| widths.get(i))
+            .max()
+            .copied()
+            .unwrap_or_default();
+
+        layouts.push(width.to_string());
+    }
+
+    layouts
+}
+
+/// The size of a padded enum, including the variant selector.
+fn padded_enum_size(variant_widths: &[Vec<u8>]) -> usize {
+    variant_widths
+        .iter()
+        .map(|widths| widths.len())
+        .max()
+        .unwrap_or_default()
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{DojoParser, DojoTokenizer};
+
+    #[test]
+    fn test_build_padded_enum_layouts() {
+        // Idle, Moving(u32, u32), Attacking(u64)
+        let variant_widths = vec![vec![], vec![32, 32], vec![64]];
+
+        assert_eq!(padded_enum_size(&variant_widths), 3);
+        assert_eq!(
+            build_padded_enum_layouts(&variant_widths),
+            vec!["8", "64", "32"]
+        );
+    }
+
+    #[test]
+    fn test_packed_enum_with_different_sizes() {
+        let db = SimpleParserDatabase::default();
+        let input = "
+        #[derive(IntrospectPacked, Serde, Drop)]
+        enum State {
+            Idle,
+            Moving: (u32, u32),
+            Attacking: u64,
+        }";
+
+        let enum_ast = DojoParser::parse_and_find_enum(
+            &db,
+            &TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+        .unwrap();
+
+        let res = DojoEnumIntrospect::process(&db, &enum_ast, true);
+        let output = res.token_stream.to_string();
+
+        assert!(res.diagnostics.is_empty());
+        assert!(output.contains("Option::Some(3)"));
+        assert!(!output.contains("core::serde::Serde<State>"));
+    }
+}
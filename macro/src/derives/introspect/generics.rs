@@ -1,38 +1,145 @@
 use cairo_lang_parser::utils::SimpleParserDatabase;
 use cairo_lang_syntax::node::{
     ast::{GenericParam, OptionWrappedGenericParamList},
-    Terminal,
+    Terminal, TypedSyntaxNode,
 };
 
 // Extract generic type information and build the
-// type and impl information to add to the generated introspect
+// type and impl information to add to the generated introspect.
+//
+// - type and const params are used as generic arguments of the introspected type,
+// - impl params (i.e `impl TDrop: Drop<T>` or `+Serde<T>`) are forwarded as is,
+// so the bounds required by the type definition also hold for the generated impl,
+// - type params which are actually used by the stored members (`member_types`)
+// require an `Introspect` bound.
 pub(crate) fn build_generic_types_and_impls(
     db: &SimpleParserDatabase,
     generic_params: OptionWrappedGenericParamList,
+    member_types: &[String],
 ) -> (Vec<String>, String) {
-    let generic_types =
+    let params =
         if let OptionWrappedGenericParamList::WrappedGenericParamList(params) = generic_params {
-            params
-                .generic_params(db)
-                .elements(db)
-                .iter()
-                .filter_map(|el| {
-                    if let GenericParam::Type(typ) = el {
-                        Some(typ.name(db).text(db).to_string())
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>()
+            params.generic_params(db).elements(db)
         } else {
             vec![]
         };
 
-    let generic_impls = generic_types
-        .iter()
-        .map(|g| format!("{g}, impl {g}Introspect: dojo::meta::introspect::Introspect<{g}>"))
-        .collect::<Vec<_>>()
-        .join(", ");
+    let mut generic_types = vec![];
+    let mut generic_impls = vec![];
+    let mut introspect_impls = vec![];
 
-    (generic_types, generic_impls)
+    for param in params.iter() {
+        match param {
+            GenericParam::Type(typ) => {
+                let name = typ.name(db).text(db).to_string();
+
+                if is_generic_param_used(&name, member_types) {
+                    introspect_impls.push(format!(
+                        "impl {name}Introspect: dojo::meta::introspect::Introspect<{name}>"
+                    ));
+                }
+
+                generic_impls.push(name.clone());
+                generic_types.push(name);
+            }
+            GenericParam::Const(c) => {
+                let name = c.name(db).text(db).to_string();
+                let ty = c
+                    .ty(db)
+                    .as_syntax_node()
+                    .get_text_without_trivia(db)
+                    .to_string();
+
+                generic_impls.push(format!("const {name}: {ty}"));
+                generic_types.push(name);
+            }
+            _ => {
+                generic_impls.push(param.as_syntax_node().get_text_without_trivia(db));
+            }
+        }
+    }
+
+    generic_impls.extend(introspect_impls);
+
+    (generic_types, generic_impls.join(", "))
+}
+
+// Check if a generic param name appears as a standalone identifier
+// in at least one of the provided member types.
+fn is_generic_param_used(name: &str, member_types: &[String]) -> bool {
+    member_types.iter().any(|ty| {
+        ty.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .any(|segment| segment == name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{DojoParser, DojoTokenizer};
+    use cairo_lang_macro::TokenStream;
+
+    #[test]
+    fn test_build_generic_types_and_impls() {
+        let db = SimpleParserDatabase::default();
+        let input = "
+        struct S<T, U, const N: u32, impl TDrop: Drop<T>, +Serde<U>> {
+            x: Array<T>,
+            y: [u8; N],
+        }";
+
+        let struct_ast = DojoParser::parse_and_find_struct(
+            &db,
+            &TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+        .unwrap();
+
+        let (types, impls) = build_generic_types_and_impls(
+            &db,
+            struct_ast.generic_params(&db),
+            &["Array<T>".to_string(), "[u8; N]".to_string()],
+        );
+
+        assert_eq!(types, vec!["T", "U", "N"]);
+        assert_eq!(
+            impls,
+            "T, U, const N: u32, impl TDrop: Drop<T>, +Serde<U>, \
+            impl TIntrospect: dojo::meta::introspect::Introspect<T>"
+        );
+    }
+
+    #[test]
+    fn test_build_generic_impl_only_params() {
+        let db = SimpleParserDatabase::default();
+        let input = "
+        struct S<+core::traits::Drop<felt252>> {
+            x: u8,
+        }";
+
+        let struct_ast = DojoParser::parse_and_find_struct(
+            &db,
+            &TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+        .unwrap();
+
+        let (types, impls) =
+            build_generic_types_and_impls(&db, struct_ast.generic_params(&db), &["u8".to_string()]);
+
+        assert!(types.is_empty());
+        assert_eq!(impls, "+core::traits::Drop<felt252>");
+
+        let introspect = super::super::generate_introspect(
+            &"S".to_string(),
+            "Option::None",
+            &types,
+            impls,
+            "dojo::meta::Layout::Fixed([].span())",
+            "dojo::meta::introspect::Ty::ByteArray",
+        )
+        .to_string();
+
+        assert!(introspect.contains(
+            "SIntrospect<+core::traits::Drop<felt252>> of dojo::meta::introspect::Introspect<S>"
+        ));
+    }
 }
@@ -57,10 +57,7 @@ pub fn build_array_layout_from_type(diagnostics: &mut Vec<Diagnostic>, item_type
             )"
         )
     } else {
-        format!(
-            "dojo::meta::introspect::Introspect::<{}>::layout()",
-            item_type
-        )
+        build_custom_layout_from_type(item_type)
     }
 }
 
@@ -106,15 +103,41 @@ pub fn build_item_layout_from_type(diagnostics: &mut Vec<Diagnostic>, item_type:
             );
         }
 
-        format!(
+        build_custom_layout_from_type(item_type)
+    }
+}
+
+/// Build the layout of a custom type (struct, enum, ...), using the static layout
+/// of this type if it is already known, or its `Introspect` implementation otherwise.
+pub fn build_custom_layout_from_type(item_type: &str) -> String {
+    match super::cache::get(item_type) {
+        Some(introspection) => introspection.layout,
+        None => format!(
             "dojo::meta::introspect::Introspect::<{}>::layout()",
             item_type
+        ),
+    }
+}
+
+/// Build the fixed layout of a packed type from the layouts of its items.
+pub fn build_packed_layout(layouts: &[String]) -> String {
+    if layouts.iter().any(|v| is_custom_layout(v.as_str())) {
+        generate_cairo_code_for_fixed_layout_with_custom_types(layouts)
+    } else {
+        format!(
+            "dojo::meta::Layout::Fixed(
+            array![
+            {}
+            ].span()
+        )",
+            layouts.join(",")
         )
     }
 }
 
 pub fn is_custom_layout(layout: &str) -> bool {
     layout.starts_with("dojo::meta::introspect::Introspect::")
+        || layout.starts_with("dojo::meta::layout::narrow_enum_layout::")
 }
 
 pub fn generate_cairo_code_for_fixed_layout_with_custom_types(layouts: &[String]) -> String {
@@ -202,6 +225,10 @@ pub fn get_packed_item_layout_from_type(
                 .map(|x| x.to_string())
                 .collect::<Vec<_>>()
                 .join(",")]
+        } else if let Some(packed_layout) =
+            super::cache::get(item_type).and_then(|i| i.packed_layout)
+        {
+            vec![packed_layout.join(",")]
         } else {
             // as we cannot verify that an enum/struct custom type is packable,
             // we suppose it is and let the user verify this.
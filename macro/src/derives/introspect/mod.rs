@@ -5,6 +5,7 @@ use cairo_lang_syntax::node::{ast, TypedSyntaxNode};
 
 use crate::helpers::{DojoTokenizer, ProcMacroResultExt};
 
+mod cache;
 mod enums;
 mod generics;
 mod layout;
@@ -12,27 +13,71 @@ mod size;
 mod structs;
 
 mod ty;
-mod utils;
+pub(crate) mod utils;
+
+/// Returns the size of a type whose introspection data are known at expansion time,
+/// `Some(None)` meaning that the size of the type is dynamic.
+pub(crate) fn get_static_size(item_type: &str) -> Option<Option<u32>> {
+    cache::get(item_type).map(|introspection| introspection.size)
+}
+
+/// Returns true if a value of the given type may contain a packed enum with variants
+/// of different sizes, whose data are padded in the world storage. Custom types are
+/// assumed to contain one, unless their introspection data are known at expansion time.
+pub(crate) fn may_contain_padded_enum(item_type: &str) -> bool {
+    let item_type = item_type.trim();
+
+    if utils::is_primitive_type(item_type) || utils::is_byte_array(item_type) {
+        false
+    } else if utils::is_array(item_type) {
+        may_contain_padded_enum(&utils::get_array_item_type(item_type))
+    } else if utils::is_tuple(item_type) {
+        utils::get_tuple_item_types(item_type)
+            .iter()
+            .any(|t| may_contain_padded_enum(t))
+    } else {
+        cache::get(item_type).map_or(true, |introspection| introspection.padded)
+    }
+}
+
+/// Generate the introspection of a model from its original struct, as the struct
+/// re-emitted by the model attribute no longer has the member attributes
+/// (`#[skip]`, `#[bits(N)]`) required to build its layout.
+pub(crate) fn process_model(
+    db: &SimpleParserDatabase,
+    struct_ast: &ast::ItemStruct,
+    is_packed: bool,
+) -> ProcMacroResult {
+    structs::DojoStructIntrospect::process_model(db, struct_ast, is_packed)
+}
+
+/// Run the expansion of an item, whose origin is used to share the introspection
+/// data of the types of its file (see `cache`).
+pub(crate) fn with_item_origin<R>(token_stream: &TokenStream, expand: impl FnOnce() -> R) -> R {
+    cache::with_origin(cache::ItemOrigin::from_token_stream(token_stream), expand)
+}
 
 pub(crate) fn process(token_stream: TokenStream, is_packed: bool) -> ProcMacroResult {
-    let db = SimpleParserDatabase::default();
-    let (root_node, _diagnostics) = db.parse_token_stream(&token_stream);
-
-    for n in root_node.descendants(&db) {
-        match n.kind(&db) {
-            ItemStruct => {
-                let struct_ast = ast::ItemStruct::from_syntax_node(&db, n);
-                return structs::DojoStructIntrospect::process(&db, &struct_ast, is_packed);
-            }
-            ItemEnum => {
-                let enum_ast = ast::ItemEnum::from_syntax_node(&db, n);
-                return enums::DojoEnumIntrospect::process(&db, &enum_ast, is_packed);
+    with_item_origin(&token_stream, || {
+        let db = SimpleParserDatabase::default();
+        let (root_node, _diagnostics) = db.parse_token_stream(&token_stream);
+
+        for n in root_node.descendants(&db) {
+            match n.kind(&db) {
+                ItemStruct => {
+                    let struct_ast = ast::ItemStruct::from_syntax_node(&db, n);
+                    return structs::DojoStructIntrospect::process(&db, &struct_ast, is_packed);
+                }
+                ItemEnum => {
+                    let enum_ast = ast::ItemEnum::from_syntax_node(&db, n);
+                    return enums::DojoEnumIntrospect::process(&db, &enum_ast, is_packed);
+                }
+                _ => {}
             }
-            _ => {}
         }
-    }
 
-    ProcMacroResult::fail("derive Introspect: unsupported syntax node.".to_string())
+        ProcMacroResult::fail("derive Introspect: unsupported syntax node.".to_string())
+    })
 }
 
 /// Generate the introspect impl for a Struct or an Enum,
@@ -45,8 +90,11 @@ pub(crate) fn generate_introspect(
     layout: &str,
     ty: &str,
 ) -> TokenStream {
-    let impl_decl = if generic_types.is_empty() {
+    let impl_decl = if generic_types.is_empty() && generic_impls.is_empty() {
         format!("{name}Introspect of dojo::meta::introspect::Introspect<{name}>")
+    } else if generic_types.is_empty() {
+        // impl-only generic params (i.e `struct S<+Drop<felt252>>`).
+        format!("{name}Introspect<{generic_impls}> of dojo::meta::introspect::Introspect<{name}>")
     } else {
         format!(
             "{name}Introspect<{generic_impls}> of dojo::meta::introspect::Introspect<{name}<{}>>",
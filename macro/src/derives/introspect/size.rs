@@ -99,6 +99,11 @@ pub fn compute_item_size_from_type(item_type: &String) -> Vec<String> {
 
         if let Some(p) = primitives.get(item_type) {
             vec![p.0.to_string()]
+        } else if let Some(introspection) = super::cache::get(item_type) {
+            match introspection.size {
+                Some(size) => vec![size.to_string()],
+                None => vec!["Option::None".to_string()],
+            }
         } else {
             vec![format!(
                 "dojo::meta::introspect::Introspect::<{}>::size()",
@@ -8,8 +8,9 @@ use cairo_lang_syntax::node::helpers::QueryAttrs;
 use cairo_lang_syntax::node::{Terminal, TypedSyntaxNode};
 use starknet::core::utils::get_selector_from_name;
 
-use crate::constants::CAIRO_DELIMITERS;
-use crate::helpers::{DiagnosticsExt, DojoChecker, ProcMacroResultExt};
+use super::utils::{is_array, is_byte_array, is_primitive_type, is_tuple};
+use crate::constants::{CAIRO_DELIMITERS, NARROWABLE_TYPES};
+use crate::helpers::{DiagnosticsExt, DojoChecker, DojoParser, ProcMacroResultExt};
 
 #[derive(Debug)]
 pub struct DojoStructIntrospect {
@@ -31,13 +32,33 @@ impl DojoStructIntrospect {
         let mut introspect = DojoStructIntrospect::new();
 
         let derive_attrs = struct_ast.attributes(db).query_attr(db, "derive");
-        DojoChecker::check_derive_conflicts(db, &mut introspect.diagnostics, derive_attrs);
+        DojoChecker::check_derive_conflicts(db, &mut introspect.diagnostics, derive_attrs.clone());
+        DojoChecker::check_skipped_members_serde(
+            db,
+            &mut introspect.diagnostics,
+            &struct_ast.name(db).text(db),
+            derive_attrs,
+            &struct_ast.members(db).elements(db),
+        );
 
         let token = introspect.generate(db, struct_ast, is_packed);
 
         ProcMacroResult::finalize(token, introspect.diagnostics)
     }
 
+    /// Generate the introspection of a model struct, from its original definition.
+    /// The derive attributes of a model are checked by the model attribute itself.
+    pub fn process_model(
+        db: &SimpleParserDatabase,
+        struct_ast: &ItemStruct,
+        is_packed: bool,
+    ) -> ProcMacroResult {
+        let mut introspect = DojoStructIntrospect::new();
+        let token = introspect.generate(db, struct_ast, is_packed);
+
+        ProcMacroResult::finalize(token, introspect.diagnostics)
+    }
+
     fn generate(
         &mut self,
         db: &SimpleParserDatabase,
@@ -48,21 +69,54 @@ impl DojoStructIntrospect {
         let struct_size = self.compute_struct_layout_size(db, struct_ast, is_packed);
         let ty = self.build_struct_ty(db, &struct_name, struct_ast);
 
-        let layout = if is_packed {
-            self.build_packed_struct_layout(db, struct_ast)
+        let (layout, packed_layout) = if is_packed {
+            let layouts = self.build_packed_struct_layouts(db, struct_ast);
+            (super::layout::build_packed_layout(&layouts), Some(layouts))
         } else {
-            format!(
+            let layout = format!(
                 "dojo::meta::Layout::Struct(
                 array![
                 {}
                 ].span()
             )",
                 self.build_struct_field_layouts(db, struct_ast)
-            )
+            );
+            (layout, None)
         };
 
-        let (gen_types, gen_impls) =
-            super::generics::build_generic_types_and_impls(db, struct_ast.generic_params(db));
+        let member_types = struct_ast
+            .members(db)
+            .elements(db)
+            .iter()
+            .filter(|m| !m.has_attr(db, "skip"))
+            .map(|m| {
+                m.type_clause(db)
+                    .ty(db)
+                    .as_syntax_node()
+                    .get_text_without_trivia(db)
+            })
+            .collect::<Vec<_>>();
+
+        let (gen_types, gen_impls) = super::generics::build_generic_types_and_impls(
+            db,
+            struct_ast.generic_params(db),
+            &member_types,
+        );
+
+        if self.diagnostics.is_empty() {
+            let padded = member_types
+                .iter()
+                .any(|t| super::may_contain_padded_enum(t));
+
+            super::cache::register(
+                &struct_name,
+                &gen_types,
+                &layout,
+                &struct_size,
+                packed_layout,
+                padded,
+            );
+        }
 
         super::generate_introspect(
             &struct_name,
@@ -88,7 +142,7 @@ impl DojoStructIntrospect {
             .elements(db)
             .into_iter()
             .filter_map(|m| {
-                if m.has_attr(db, "key") {
+                if m.has_attr(db, "key") || m.has_attr(db, "skip") {
                     return None;
                 }
 
@@ -111,19 +165,53 @@ impl DojoStructIntrospect {
 
     pub fn build_member_ty(&self, db: &SimpleParserDatabase, member: &Member) -> String {
         let name = member.name(db).text(db).to_string();
-        let attrs = if member.has_attr(db, "key") {
+        let mut attrs = if member.has_attr(db, "key") {
             vec!["'key'"]
         } else {
             vec![]
         };
 
+        // invalid bit widths are reported while building the layout.
+        let bits = DojoParser::parse_member_bits(db, member, &mut vec![])
+            .map(|bits| format!("'bits:{bits}'"));
+
+        if let Some(bits) = &bits {
+            attrs.push(bits);
+        }
+
+        // The default value is always the last attribute: the 'default' marker
+        // followed by the length of the serialized value and the serialized value.
+        // Invalid default values are reported by the model attribute.
+        let attrs = match DojoParser::parse_member_default_value(db, member, &mut vec![]) {
+            Some(value) => {
+                let member_type = member
+                    .type_clause(db)
+                    .ty(db)
+                    .as_syntax_node()
+                    .get_text_without_trivia(db);
+
+                format!(
+                    "{{
+                let mut attrs = array![{}];
+                let mut serialized = array![];
+                core::serde::Serde::<{member_type}>::serialize(@({value}), ref serialized);
+                attrs.append('default');
+                attrs.append(serialized.len().into());
+                attrs.append_span(serialized.span());
+                attrs.span()
+            }}",
+                    attrs.join(",")
+                )
+            }
+            None => format!("array![{}].span()", attrs.join(",")),
+        };
+
         format!(
             "dojo::meta::introspect::Member {{
             name: '{name}',
-            attrs: array![{}].span(),
+            attrs: {attrs},
             ty: {}
         }}",
-            attrs.join(","),
             super::ty::build_ty_from_type_clause(db, &member.type_clause(db))
         )
     }
@@ -138,6 +226,7 @@ impl DojoStructIntrospect {
             .members(db)
             .elements(db)
             .iter()
+            .filter(|m| !m.has_attr(db, "skip"))
             .map(|m| self.build_member_ty(db, m))
             .collect::<Vec<_>>();
 
@@ -163,7 +252,12 @@ impl DojoStructIntrospect {
     ) -> String {
         let mut members = vec![];
 
-        for member in struct_ast.members(db).elements(db).iter() {
+        for member in struct_ast
+            .members(db)
+            .elements(db)
+            .iter()
+            .filter(|m| !m.has_attr(db, "skip"))
+        {
             if member.has_attr(db, "key") {
                 let member_type = member.type_clause(db).ty(db).as_syntax_node().get_text(db);
 
@@ -178,6 +272,14 @@ impl DojoStructIntrospect {
                 }
             } else {
                 let field_name = member.name(db).text(db);
+
+                if member.has_attr(db, "bits") {
+                    self.diagnostics.push_error(format!(
+                        "The bits attribute of the member '{field_name}' requires the struct \
+                        to derive IntrospectPacked."
+                    ));
+                }
+
                 let field_selector = get_selector_from_name(field_name.as_ref()).unwrap();
                 let field_layout = super::layout::get_layout_from_type_clause(
                     db,
@@ -197,47 +299,94 @@ impl DojoStructIntrospect {
         members.join(",\n")
     }
 
-    fn build_packed_struct_layout(
+    fn build_packed_struct_layouts(
         &mut self,
         db: &SimpleParserDatabase,
         struct_ast: &ItemStruct,
-    ) -> String {
+    ) -> Vec<String> {
         let mut layouts = vec![];
 
         for member in struct_ast
             .members(db)
             .elements(db)
             .iter()
-            .filter(|m| !m.has_attr(db, "key"))
+            .filter(|m| !m.has_attr(db, "key") && !m.has_attr(db, "skip"))
         {
             let layout = super::layout::get_packed_field_layout_from_type_clause(
                 db,
                 &mut self.diagnostics,
                 &member.type_clause(db),
             );
-            layouts.push(layout)
+
+            match DojoParser::parse_member_bits(db, member, &mut self.diagnostics) {
+                Some(bits) => {
+                    layouts.push(self.narrow_packed_member_layout(db, member, &layout, bits))
+                }
+                None => layouts.push(layout),
+            }
         }
 
-        let layouts = layouts.into_iter().flatten().collect::<Vec<_>>();
+        layouts.into_iter().flatten().collect::<Vec<_>>()
+    }
 
-        if layouts
-            .iter()
-            .any(|v| super::layout::is_custom_layout(v.as_str()))
-        {
-            super::layout::generate_cairo_code_for_fixed_layout_with_custom_types(&layouts)
-        } else {
-            format!(
-                "dojo::meta::Layout::Fixed(
-            array![
-            {}
-            ].span()
-        )",
-                layouts.join(",")
-            )
+    /// Replace the packed layout of a member by the bit width set with `#[bits(N)]`.
+    /// Unsigned integers are serialized into a single value, which is range checked
+    /// when the model is written.
+    ///
+    /// For enums, only the variant selector is narrowed. As the enum type is not known
+    /// at expansion time, its layout is narrowed by `narrow_enum_layout`, which also
+    /// checks that the selectors of all the variants fit in the bit width.
+    fn narrow_packed_member_layout(
+        &mut self,
+        db: &SimpleParserDatabase,
+        member: &Member,
+        layout: &[String],
+        bits: u8,
+    ) -> Vec<String> {
+        let name = member.name(db).text(db);
+        let member_type = member
+            .type_clause(db)
+            .ty(db)
+            .as_syntax_node()
+            .get_text_without_trivia(db);
+
+        if is_custom_type(&member_type) {
+            return vec![format!(
+                "dojo::meta::layout::narrow_enum_layout::<{member_type}>({bits})"
+            )];
+        }
+
+        let width = match layout {
+            [width] if NARROWABLE_TYPES.contains(&member_type.as_str()) => width.parse::<u8>().ok(),
+            _ => None,
+        };
+
+        match width {
+            // narrowing to the full width would not be visible from the model layout.
+            Some(width) if bits < width => vec![bits.to_string()],
+            Some(width) => {
+                self.diagnostics.push_error(format!(
+                    "The member '{name}' must use less than {width} bits, as its type \
+                    '{member_type}' is packed into {width} bits."
+                ));
+                layout.to_vec()
+            }
+            None => {
+                self.diagnostics.push_error(format!(
+                    "The bits attribute of the member '{name}' can only be used on unsigned \
+                    integers (u8 to u128) and enums."
+                ));
+                layout.to_vec()
+            }
         }
     }
 }
 
+/// Custom types may be enums, whose layout is checked by `narrow_enum_layout`.
+fn is_custom_type(ty: &str) -> bool {
+    !is_primitive_type(ty) && !is_tuple(ty) && !is_array(ty) && !is_byte_array(ty)
+}
+
 fn type_contains_usize(type_str: String) -> bool {
     type_str.contains("usize")
         && type_str
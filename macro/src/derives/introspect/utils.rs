@@ -21,6 +21,10 @@ pub fn primitive_type_introspection() -> HashMap<String, TypeIntrospection> {
     ])
 }
 
+pub fn is_primitive_type(ty: &str) -> bool {
+    primitive_type_introspection().contains_key(ty)
+}
+
 /// Check if the provided type is an unsupported `Option<T>`,
 /// because tuples are not supported with Option.
 pub fn is_unsupported_option_type(ty: &str) -> bool {
@@ -0,0 +1,419 @@
+use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::node::ast::{self, ArgClause, BinaryOperator, OptionTypeClause, Pattern};
+use cairo_lang_syntax::node::kind::SyntaxKind::{
+    ExprBinary, FunctionWithBody, StatementLet, TraitItemFunction,
+};
+use cairo_lang_syntax::node::{SyntaxNode, Terminal, TypedSyntaxNode};
+use dojo_types::system::Dependency;
+
+const READ_FNS: [&str; 4] = ["read_model", "read_models", "read_value", "read_values"];
+const WRITE_FNS: [&str; 6] = [
+    "write_model",
+    "write_models",
+    "erase_model",
+    "erase_models",
+    "emit_event",
+    "emit_events",
+];
+
+pub struct DojoAnalyzer {}
+
+/// DojoAnalyzer provides some static analysis of Cairo code.
+impl DojoAnalyzer {
+    /// Find the models (and events) read and written by the code of the provided node,
+    /// by looking for world storage calls with explicitly typed models:
+    /// - `let position: Position = world.read_model(player);`
+    /// - `let position = world.read_model::<Position>(player);`
+    /// - `world.write_model(@Position { ... });`
+    /// - `world.erase_model(@position);` where `position` has a known type.
+    ///
+    /// Models are identified by their type name, and variable types are
+    /// scoped to the function in which they are declared. A shadowed variable
+    /// has the type of the last binding declared before its use.
+    pub fn find_model_dependencies(
+        db: &SimpleParserDatabase,
+        node: &SyntaxNode,
+    ) -> Vec<Dependency> {
+        let mut dependencies: Vec<Dependency> = vec![];
+
+        for n in node
+            .descendants(db)
+            .filter(|n| matches!(n.kind(db), FunctionWithBody | TraitItemFunction))
+        {
+            for (name, read, write) in find_function_dependencies(db, &n) {
+                match dependencies.iter_mut().find(|d| d.name == name) {
+                    Some(dependency) => {
+                        dependency.read |= read;
+                        dependency.write |= write;
+                    }
+                    None => dependencies.push(Dependency { name, read, write }),
+                }
+            }
+        }
+
+        dependencies
+    }
+}
+
+/// Find the models read and written by a function, as (name, read, write) tuples.
+fn find_function_dependencies(
+    db: &SimpleParserDatabase,
+    function: &SyntaxNode,
+) -> Vec<(String, bool, bool)> {
+    let mut dependencies = vec![];
+    let mut bindings: Vec<Binding> = vec![];
+
+    // First, collect the type of variables and the reads typed by their variable.
+    for n in function
+        .descendants(db)
+        .filter(|n| n.kind(db) == StatementLet)
+    {
+        let let_ast = ast::StatementLet::from_syntax_node(db, n);
+        let call = get_method_call(db, &let_ast.rhs(db));
+
+        let ty = match let_ast.type_clause(db) {
+            OptionTypeClause::TypeClause(type_clause) => Some(
+                type_clause
+                    .ty(db)
+                    .as_syntax_node()
+                    .get_text_without_trivia(db),
+            ),
+            OptionTypeClause::Empty(_) => match let_ast.rhs(db) {
+                ast::Expr::StructCtorCall(ctor) => {
+                    Some(ctor.path(db).as_syntax_node().get_text_without_trivia(db))
+                }
+                // `let position = world.read_model::<Position>(player);`
+                _ => call
+                    .as_ref()
+                    .filter(|call| READ_FNS.contains(&call.name.as_str()))
+                    .and_then(|call| call.generic_arg.clone()),
+            },
+        };
+
+        // variables of unknown types are bound too, as they may shadow a typed one.
+        if let Pattern::Identifier(ident) = let_ast.pattern(db) {
+            bindings.push(Binding {
+                name: ident.name(db).text(db).to_string(),
+                ty: ty.clone(),
+                statement: let_ast.as_syntax_node(),
+            });
+        }
+
+        let Some(ty) = ty else {
+            continue;
+        };
+
+        // reads with generic args are collected with the other method calls.
+        if let Some(call) = call.filter(|call| call.generic_arg.is_none()) {
+            if READ_FNS.contains(&call.name.as_str()) {
+                dependencies.push((get_read_model_name(&call.name, &ty), true, false));
+            }
+        }
+    }
+
+    // Then, find the reads from their generic args, and the writes from their
+    // generic args or the type of their first argument.
+    for n in function
+        .descendants(db)
+        .filter(|n| n.kind(db) == ExprBinary)
+    {
+        let expr = ast::Expr::Binary(ast::ExprBinary::from_syntax_node(db, n));
+
+        let Some(call) = get_method_call(db, &expr) else {
+            continue;
+        };
+
+        if READ_FNS.contains(&call.name.as_str()) {
+            if let Some(ty) = &call.generic_arg {
+                dependencies.push((get_read_model_name(&call.name, ty), true, false));
+            }
+            continue;
+        }
+
+        if !WRITE_FNS.contains(&call.name.as_str()) {
+            continue;
+        }
+
+        let ty = call
+            .generic_arg
+            .clone()
+            .or_else(|| match call.args.first() {
+                Some(ast::Expr::Unary(unary)) => get_expr_type(db, &unary.expr(db), &bindings),
+                Some(arg) => get_expr_type(db, arg, &bindings),
+                None => None,
+            });
+
+        if let Some(ty) = ty {
+            dependencies.push((get_model_name(&ty), false, true));
+        }
+    }
+
+    dependencies
+}
+
+/// A variable bound by a `let` statement, with its type if known.
+struct Binding {
+    name: String,
+    ty: Option<String>,
+    statement: SyntaxNode,
+}
+
+/// A method call like `world.write_model(...)` or `world.read_model::<T>(...)`.
+struct MethodCall {
+    name: String,
+    generic_arg: Option<String>,
+    args: Vec<ast::Expr>,
+}
+
+/// If the expression is a method call, returns the method name,
+/// its generic argument if any and its unnamed arguments.
+fn get_method_call(db: &SimpleParserDatabase, expr: &ast::Expr) -> Option<MethodCall> {
+    let ast::Expr::Binary(binary) = expr else {
+        return None;
+    };
+
+    if !matches!(binary.op(db), BinaryOperator::Dot(_)) {
+        return None;
+    }
+
+    let ast::Expr::FunctionCall(call) = binary.rhs(db) else {
+        return None;
+    };
+
+    // split the function name and its generic args (i.e `read_model::<T>`).
+    let path = call.path(db).as_syntax_node().get_text_without_trivia(db);
+    let (name, generic_arg) = match path.split_once("::<") {
+        Some((name, generic_arg)) => (
+            name.to_string(),
+            generic_arg
+                .strip_suffix('>')
+                .map(|arg| arg.trim().to_string()),
+        ),
+        None => (path.split("::").next()?.to_string(), None),
+    };
+
+    let args = call
+        .arguments(db)
+        .arguments(db)
+        .elements(db)
+        .iter()
+        .filter_map(|arg| match arg.arg_clause(db) {
+            ArgClause::Unnamed(clause) => Some(clause.value(db)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    Some(MethodCall {
+        name,
+        generic_arg,
+        args,
+    })
+}
+
+/// Get the model name read by a read function, from the type of the read value.
+fn get_read_model_name(fn_name: &str, ty: &str) -> String {
+    let model = get_model_name(ty);
+
+    if fn_name.starts_with("read_value") {
+        model.strip_suffix("Value").unwrap_or(&model).to_string()
+    } else {
+        model
+    }
+}
+
+/// Get the type of a struct constructor or of a variable with a known type.
+/// A variable has the type of its last binding declared before the expression.
+fn get_expr_type(
+    db: &SimpleParserDatabase,
+    expr: &ast::Expr,
+    bindings: &[Binding],
+) -> Option<String> {
+    match expr {
+        ast::Expr::StructCtorCall(ctor) => {
+            Some(ctor.path(db).as_syntax_node().get_text_without_trivia(db))
+        }
+        ast::Expr::Path(path) => {
+            let name = path.as_syntax_node().get_text_without_trivia(db);
+            let start = path.as_syntax_node().span(db).start;
+
+            bindings
+                .iter()
+                .rev()
+                .find(|b| b.name == name && b.statement.span(db).end <= start)
+                .and_then(|b| b.ty.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Get the model name from a type, removing the module path and
+/// the `Array`/`Span` wrappers (i.e `Span<models::Position>` -> `Position`).
+fn get_model_name(ty: &str) -> String {
+    let mut ty = ty.trim();
+
+    for wrapper in ["Array<", "Span<"] {
+        if let Some(inner) = ty.strip_prefix(wrapper).and_then(|t| t.strip_suffix('>')) {
+            ty = inner.trim();
+        }
+    }
+
+    let ty = ty.split('<').next().unwrap_or(ty);
+    ty.rsplit("::").next().unwrap_or(ty).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{DojoParser, DojoTokenizer};
+    use cairo_lang_macro::TokenStream;
+
+    #[test]
+    fn test_get_model_name() {
+        assert_eq!(get_model_name("Position"), "Position");
+        assert_eq!(get_model_name("models::Position"), "Position");
+        assert_eq!(get_model_name("Array<Position>"), "Position");
+        assert_eq!(get_model_name("Span<models::Moves>"), "Moves");
+    }
+
+    #[test]
+    fn test_find_model_dependencies() {
+        let db = SimpleParserDatabase::default();
+        let input = "
+        mod actions {
+            fn spawn(ref self: ContractState) {
+                let mut world = self.world_default();
+                let position: Position = world.read_model(player);
+                let moves = Moves { player, remaining: 10 };
+
+                world.write_model(@moves);
+                world.write_model(@Vec2 { x: 1, y: 2 });
+                world.emit_event(@Moved { player });
+            }
+        }";
+
+        let module_ast = DojoParser::parse_and_find_module(
+            &db,
+            &TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+        .unwrap();
+
+        let dependencies = DojoAnalyzer::find_model_dependencies(&db, &module_ast.as_syntax_node());
+
+        assert_eq!(
+            dependencies,
+            vec![
+                Dependency {
+                    name: "Position".to_string(),
+                    read: true,
+                    write: false
+                },
+                Dependency {
+                    name: "Moves".to_string(),
+                    read: false,
+                    write: true
+                },
+                Dependency {
+                    name: "Vec2".to_string(),
+                    read: false,
+                    write: true
+                },
+                Dependency {
+                    name: "Moved".to_string(),
+                    read: false,
+                    write: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_model_dependencies_per_function() {
+        let db = SimpleParserDatabase::default();
+        let input = "
+        mod actions {
+            fn spawn(ref self: ContractState) {
+                let mut world = self.world_default();
+                let model: Position = world.read_model(player);
+                world.write_model(@model);
+            }
+
+            fn move(ref self: ContractState) {
+                let mut world = self.world_default();
+                let model = world.read_model::<Moves>(player);
+                let direction = world.read_value::<DirectionValue>(player).direction;
+                world.write_model(@model);
+            }
+        }";
+
+        let module_ast = DojoParser::parse_and_find_module(
+            &db,
+            &TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+        .unwrap();
+
+        let dependencies = DojoAnalyzer::find_model_dependencies(&db, &module_ast.as_syntax_node());
+
+        assert_eq!(
+            dependencies,
+            vec![
+                Dependency {
+                    name: "Position".to_string(),
+                    read: true,
+                    write: true
+                },
+                Dependency {
+                    name: "Moves".to_string(),
+                    read: true,
+                    write: true
+                },
+                Dependency {
+                    name: "Direction".to_string(),
+                    read: true,
+                    write: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_model_dependencies_with_shadowing() {
+        let db = SimpleParserDatabase::default();
+        let input = "
+        mod actions {
+            fn spawn(ref self: ContractState) {
+                let mut world = self.world_default();
+                let model: Position = world.read_model(player);
+                world.write_model(@model);
+                let model: Moves = world.read_model(player);
+                world.erase_model(@model);
+                let model = get_model();
+                world.write_model(@model);
+            }
+        }";
+
+        let module_ast = DojoParser::parse_and_find_module(
+            &db,
+            &TokenStream::new(vec![DojoTokenizer::tokenize(input)]),
+        )
+        .unwrap();
+
+        let dependencies = DojoAnalyzer::find_model_dependencies(&db, &module_ast.as_syntax_node());
+
+        // the last write is not detected, as `model` is shadowed by a variable of unknown type.
+        assert_eq!(
+            dependencies,
+            vec![
+                Dependency {
+                    name: "Position".to_string(),
+                    read: true,
+                    write: true
+                },
+                Dependency {
+                    name: "Moves".to_string(),
+                    read: true,
+                    write: true
+                },
+            ]
+        );
+    }
+}
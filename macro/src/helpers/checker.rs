@@ -1,9 +1,14 @@
 use cairo_lang_macro::{Diagnostic, ProcMacroResult};
 use cairo_lang_parser::utils::SimpleParserDatabase;
-use cairo_lang_syntax::node::ast::Attribute;
+use cairo_lang_syntax::node::ast::{Attribute, Member, OptionWrappedGenericParamList};
+use cairo_lang_syntax::node::helpers::QueryAttrs;
 use dojo_types::naming;
 
 use crate::constants::{DOJO_INTROSPECT_DERIVE, DOJO_PACKED_DERIVE};
+use crate::derives::introspect;
+use crate::derives::introspect::utils::{
+    get_tuple_item_types, is_array, is_byte_array, is_primitive_type, is_tuple,
+};
 use crate::helpers::{DiagnosticsExt, DojoParser, ProcMacroResultExt};
 
 pub struct DojoChecker {}
@@ -29,6 +34,87 @@ impl DojoChecker {
         }
     }
 
+    /// Be sure a struct with skipped members does not derive `Serde`,
+    /// as the derived implementation would also serialize skipped members
+    /// which are not part of the layout.
+    ///
+    /// Used by both the `Introspect` derives and the `dojo::model` attribute,
+    /// which generates a dedicated `Serde` implementation for such models.
+    pub fn check_skipped_members_serde(
+        db: &SimpleParserDatabase,
+        diagnostics: &mut Vec<Diagnostic>,
+        name: &str,
+        attrs: Vec<Attribute>,
+        members: &[Member],
+    ) {
+        if !members.iter().any(|m| m.has_attr(db, "skip")) {
+            return;
+        }
+
+        // invalid derive attributes are already reported by the callers.
+        let attr_names = DojoParser::extract_derive_attr_names(db, &mut vec![], attrs);
+
+        if attr_names.contains(&"Serde".to_string()) {
+            diagnostics.push_error(format!(
+                "'{name}' has skipped members and cannot derive Serde, as skipped members \
+                are not part of its layout."
+            ));
+        }
+    }
+
+    /// Be sure a model key has a fixed size, as the serialized keys are hashed
+    /// to compute the entity id of a model instance.
+    ///
+    /// Custom types are only checked if their introspection data are known at
+    /// expansion time (see `DOJO_STATIC_LAYOUTS`). Otherwise, they are checked
+    /// when the model contract is deployed (see [DojoChecker::get_unchecked_key_types]).
+    pub fn check_key_type(diagnostics: &mut Vec<Diagnostic>, member_name: &str, key_type: &str) {
+        if let Some(reason) = Self::find_dynamic_key_type(key_type) {
+            diagnostics.push_error(format!(
+                "The member '{member_name}' cannot be used as a key: {reason}. Keys must have \
+                a fixed size, like primitive types, tuples, or structs and enums with a fixed \
+                layout."
+            ));
+        }
+    }
+
+    /// Returns the custom types used by a model key (the key type itself or the items
+    /// of a tuple) whose size is not known at expansion time.
+    pub fn get_unchecked_key_types(key_type: &str) -> Vec<String> {
+        let key_type = key_type.trim();
+
+        if is_tuple(key_type) {
+            get_tuple_item_types(key_type)
+                .iter()
+                .flat_map(|item_type| Self::get_unchecked_key_types(item_type))
+                .collect()
+        } else if is_primitive_type(key_type) || introspect::get_static_size(key_type).is_some() {
+            vec![]
+        } else {
+            vec![key_type.to_string()]
+        }
+    }
+
+    fn find_dynamic_key_type(key_type: &str) -> Option<String> {
+        let key_type = key_type.trim();
+
+        if is_array(key_type) {
+            Some(format!("'{key_type}' is an array with a dynamic size"))
+        } else if is_byte_array(key_type) {
+            Some(format!("'{key_type}' is a string with a dynamic size"))
+        } else if key_type.starts_with("Option<") {
+            Some(format!("'{key_type}' is serialized with a variable size"))
+        } else if is_tuple(key_type) {
+            get_tuple_item_types(key_type)
+                .iter()
+                .find_map(|item_type| Self::find_dynamic_key_type(item_type))
+        } else if let Some(None) = introspect::get_static_size(key_type) {
+            Some(format!("'{key_type}' has a dynamic size"))
+        } else {
+            None
+        }
+    }
+
     /// Check if the name of a Dojo element is valid.
     pub fn is_name_valid(element: &str, name: &str) -> Option<ProcMacroResult> {
         if !naming::is_name_valid(name) {
@@ -40,4 +126,26 @@ impl DojoChecker {
 
         None
     }
+
+    /// Check that a Dojo element does not declare generic parameters,
+    /// as the generated Dojo code (value struct, parsers, contract...)
+    /// is only generated for concrete types.
+    pub fn is_not_generic(
+        db: &SimpleParserDatabase,
+        element: &str,
+        name: &str,
+        generic_params: &OptionWrappedGenericParamList,
+    ) -> Option<ProcMacroResult> {
+        if let OptionWrappedGenericParamList::WrappedGenericParamList(params) = generic_params {
+            if !params.generic_params(db).elements(db).is_empty() {
+                return Some(ProcMacroResult::fail(format!(
+                    "The {element} '{name}' cannot have generic parameters. Declare a \
+                    dedicated {element} with concrete member types for each instantiation \
+                    instead (e.g. '{name}U32', '{name}Felt')."
+                )));
+            }
+        }
+
+        None
+    }
 }
@@ -3,6 +3,7 @@ use cairo_lang_macro::{Diagnostic, Severity};
 pub trait DiagnosticsExt {
     fn with_error(message: String) -> Self;
     fn push_error(&mut self, message: String);
+    fn push_warning(&mut self, message: String);
 }
 
 impl DiagnosticsExt for Vec<Diagnostic> {
@@ -12,6 +13,9 @@ impl DiagnosticsExt for Vec<Diagnostic> {
     fn push_error(&mut self, message: String) {
         self.push(Diagnostic::error(message));
     }
+    fn push_warning(&mut self, message: String) {
+        self.push(Diagnostic::warn(message));
+    }
 }
 
 pub trait DiagnosticExt {
@@ -14,6 +14,69 @@ impl DojoFormatter {
         )
     }
 
+    /// Build serializing statement of a model value member, where the data of the
+    /// packed enums are padded as expected by the world storage.
+    pub(crate) fn serialize_padded_member_ty(member: &Member) -> String {
+        format!(
+            "dojo::storage::padding::serialize_padded(self.{}, ref serialized);\n",
+            member.name
+        )
+    }
+
+    /// Build deserializing statement of a member from the `from` span, into
+    /// a local variable named after the member and prefixed by `__`.
+    pub(crate) fn deserialize_member_ty(member: &Member, from: &str) -> String {
+        format!(
+            "let __{} = core::serde::Serde::<{}>::deserialize(ref {from})?;\n",
+            member.name, member.ty
+        )
+    }
+
+    /// Build deserializing statement of a model value member read from the world storage,
+    /// where the padding of the packed enums is removed.
+    pub(crate) fn deserialize_padded_member_ty(member: &Member, from: &str) -> String {
+        format!(
+            "let __{} = dojo::storage::padding::deserialize_padded::<{}>(ref {from})?;\n",
+            member.name, member.ty
+        )
+    }
+
+    /// Build the statement saving the position of the first serialized value of
+    /// a member narrowed with `#[bits(N)]`, to be checked by [Self::check_member_bits].
+    pub(crate) fn member_bits_offset(member: &Member) -> String {
+        format!(
+            "let __{}_offset = core::array::ArrayTrait::len(@serialized);\n",
+            member.name
+        )
+    }
+
+    /// Build the statement checking that the first serialized value of a member, which is
+    /// the value of an unsigned integer or the variant selector of an enum, fits in the bit
+    /// width set with `#[bits(N)]`.
+    pub(crate) fn check_member_bits(member: &Member, bits: u8) -> String {
+        format!(
+            "assert!(
+                core::traits::Into::<felt252, u256>::into(
+                    *core::array::ArrayTrait::at(@serialized, __{name}_offset)
+                ) < dojo::storage::packing::pow2_const({bits}),
+                \"The value of the member `{name}` exceeds {bits} bits.\"
+            );\n",
+            name = member.name
+        )
+    }
+
+    /// Return a check asserting that a custom type used by a key member has a fixed size,
+    /// for the custom types whose size is not known at expansion time.
+    pub(crate) fn check_key_size(member: &Member, key_type: &str) -> String {
+        format!(
+            "assert!(
+                core::option::OptionTrait::is_some(@dojo::meta::Introspect::<{key_type}>::size()),
+                \"The key member `{}` must have a fixed size, but `{key_type}` has a dynamic size.\"
+            );\n",
+            member.name
+        )
+    }
+
     /// Return member declaration statement from member name and type.
     pub(crate) fn get_member_declaration(name: &str, ty: &str) -> String {
         format!("pub {}: {},\n", name, ty)
@@ -1,37 +1,75 @@
 use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::node::helpers::QueryAttrs;
 use cairo_lang_syntax::node::{ast::Member, Terminal, TypedSyntaxNode};
-use starknet_crypto::{poseidon_hash_many, Felt};
+use starknet_crypto::Felt;
 
-use dojo_types::naming;
+use dojo_types::unique_hash::{self, UniqueHashMember};
 
-/// Compute a unique hash based on the element name and types and names of members.
+use crate::helpers::DojoParser;
+
+/// Compute a unique hash based on the element name, its packing and its members
+/// (names, normalized types, key flags and bit widths, in declaration order).
 /// This hash is used in element contracts to ensure uniqueness.
+/// Skipped members are not stored, so they are not part of the hash.
+///
+/// The same hash can be computed from the element schema and layout with
+/// `dojo_types::unique_hash::compute_unique_hash_from_ty`.
 pub fn compute_unique_hash(
     db: &SimpleParserDatabase,
     element_name: &str,
     is_packed: bool,
     members: &[Member],
 ) -> Felt {
-    let mut hashes = vec![
-        if is_packed { Felt::ONE } else { Felt::ZERO },
-        naming::compute_bytearray_hash(element_name),
-    ];
-    hashes.extend(
-        members
-            .iter()
-            .map(|m| {
-                poseidon_hash_many(&[
-                    naming::compute_bytearray_hash(&m.name(db).text(db).to_string()),
-                    naming::compute_bytearray_hash(
-                        m.type_clause(db)
-                            .ty(db)
-                            .as_syntax_node()
-                            .get_text(db)
-                            .trim(),
-                    ),
-                ])
-            })
-            .collect::<Vec<_>>(),
-    );
-    poseidon_hash_many(&hashes)
+    let members = members
+        .iter()
+        .filter(|m| !m.has_attr(db, "skip"))
+        .map(|m| UniqueHashMember {
+            name: m.name(db).text(db).to_string(),
+            type_path: unique_hash::normalize_type_path(
+                &m.type_clause(db).ty(db).as_syntax_node().get_text(db),
+            ),
+            key: m.has_attr(db, "key"),
+            // invalid bit widths are reported by the model and Introspect macros.
+            bits: DojoParser::parse_member_bits(db, m, &mut vec![]),
+        })
+        .collect::<Vec<_>>();
+
+    unique_hash::compute_unique_hash(element_name, is_packed, &members)
+}
+
+/// Build the name of the dispatcher function of an interface,
+/// (i.e `IMyLib` -> `my_lib_dispatcher`).
+pub fn dispatcher_fn_name(trait_name: &str) -> String {
+    let mut chars = trait_name.chars();
+
+    // skip the usual `I` prefix of interfaces.
+    if trait_name.starts_with('I') && trait_name.chars().nth(1).is_some_and(|c| c.is_uppercase()) {
+        chars.next();
+    }
+
+    let mut fn_name = String::new();
+    let mut prev_is_lower = false;
+
+    for c in chars {
+        if c.is_uppercase() && prev_is_lower {
+            fn_name.push('_');
+        }
+
+        prev_is_lower = c.is_lowercase() || c.is_ascii_digit();
+        fn_name.push(c.to_ascii_lowercase());
+    }
+
+    format!("{fn_name}_dispatcher")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatcher_fn_name() {
+        assert_eq!(dispatcher_fn_name("IMyLib"), "my_lib_dispatcher");
+        assert_eq!(dispatcher_fn_name("SimpleMath"), "simple_math_dispatcher");
+        assert_eq!(dispatcher_fn_name("Ivy"), "ivy_dispatcher");
+    }
 }
@@ -4,8 +4,17 @@ pub struct Member {
     pub name: String,
     pub ty: String,
     pub key: bool,
+    // skipped members are not persisted in the world storage.
+    pub skip: bool,
+    // expression of the default value, set with `#[default(...)]`.
+    pub default: Option<String>,
+    // bit width of a packed member, set with `#[bits(N)]`.
+    pub bits: Option<u8>,
 }
 
+pub mod analyzer;
+pub use analyzer::*;
+
 pub mod debug;
 pub use debug::*;
 
@@ -18,6 +27,8 @@ pub use checker::*;
 pub mod parser;
 pub use parser::*;
 
+pub mod registry;
+
 pub mod formatter;
 pub use formatter::*;
 
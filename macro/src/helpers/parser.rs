@@ -1,15 +1,20 @@
 use cairo_lang_macro::{Diagnostic, TokenStream};
 use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::attribute::structured::AttributeArg;
 use cairo_lang_syntax::attribute::structured::AttributeArgVariant;
 use cairo_lang_syntax::attribute::structured::AttributeStructurize;
 use cairo_lang_syntax::node::ast::Attribute;
 use cairo_lang_syntax::node::ast::Member as MemberAst;
 use cairo_lang_syntax::node::helpers::QueryAttrs;
-use cairo_lang_syntax::node::kind::SyntaxKind::{ExprParenthesized, ItemModule, ItemStruct};
+use cairo_lang_syntax::node::kind::SyntaxKind::{
+    Attribute as AttributeKind, ExprListParenthesized, ExprParenthesized, ItemEnum, ItemModule,
+    ItemStruct, ItemTrait,
+};
 use cairo_lang_syntax::node::Terminal;
 use cairo_lang_syntax::node::{ast, TypedSyntaxNode};
 
-use crate::helpers::{DiagnosticsExt, Member};
+use crate::constants::MAX_MEMBER_BITS;
+use crate::helpers::{DiagnosticsExt, DojoTokenizer, Member};
 
 pub struct DojoParser {}
 
@@ -49,6 +54,38 @@ impl DojoParser {
         None
     }
 
+    /// Parse an input token stream and return a ItemEnum syntax node if found.
+    pub(crate) fn parse_and_find_enum(
+        db: &SimpleParserDatabase,
+        token_stream: &TokenStream,
+    ) -> Option<ast::ItemEnum> {
+        let (root_node, _diagnostics) = db.parse_token_stream(token_stream);
+
+        for n in root_node.descendants(db) {
+            if n.kind(db) == ItemEnum {
+                return Some(ast::ItemEnum::from_syntax_node(db, n));
+            }
+        }
+
+        None
+    }
+
+    /// Parse an input token stream and return a ItemTrait syntax node if found.
+    pub(crate) fn parse_and_find_trait(
+        db: &SimpleParserDatabase,
+        token_stream: &TokenStream,
+    ) -> Option<ast::ItemTrait> {
+        let (root_node, _diagnostics) = db.parse_token_stream(token_stream);
+
+        for n in root_node.descendants(db) {
+            if n.kind(db) == ItemTrait {
+                return Some(ast::ItemTrait::from_syntax_node(db, n));
+            }
+        }
+
+        None
+    }
+
     /// Parse the input token stream of an inline proc macro as a
     /// parenthesized expression.
     pub(crate) fn parse_inline_args(
@@ -66,6 +103,68 @@ impl DojoParser {
         None
     }
 
+    /// Parse the input token stream of an inline proc macro as a list
+    /// of parenthesized expressions (i.e `("ns", "name")`).
+    pub(crate) fn parse_inline_args_list(
+        db: &SimpleParserDatabase,
+        token_stream: &TokenStream,
+    ) -> Option<Vec<ast::Expr>> {
+        let (root_node, _diagnostics) = db.parse_token_stream_expr(token_stream);
+
+        for n in root_node.descendants(db) {
+            match n.kind(db) {
+                ExprParenthesized => {
+                    return Some(vec![
+                        ast::ExprParenthesized::from_syntax_node(db, n).expr(db)
+                    ]);
+                }
+                ExprListParenthesized => {
+                    return Some(
+                        ast::ExprListParenthesized::from_syntax_node(db, n)
+                            .expressions(db)
+                            .elements(db),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    /// Parse the arguments of an attribute proc macro (i.e `(namespace: "ns")`).
+    /// As these arguments are not a valid Cairo item by themselves, they are
+    /// wrapped into a fake attribute to be able to use the attribute structurization.
+    pub(crate) fn parse_attribute_args(
+        db: &SimpleParserDatabase,
+        args: &TokenStream,
+    ) -> Vec<AttributeArg> {
+        let args = args.to_string();
+        let args = args.trim();
+
+        if args.is_empty() {
+            return vec![];
+        }
+
+        let args = if args.starts_with('(') && args.ends_with(')') {
+            args.to_string()
+        } else {
+            format!("({args})")
+        };
+
+        let item = format!("#[dojo_attribute_args{args}]\nfn dojo_attribute_args() {{}}");
+        let (root_node, _diagnostics) =
+            db.parse_token_stream(&TokenStream::new(vec![DojoTokenizer::tokenize(&item)]));
+
+        for n in root_node.descendants(db) {
+            if n.kind(db) == AttributeKind {
+                return ast::Attribute::from_syntax_node(db, n).structurize(db).args;
+            }
+        }
+
+        vec![]
+    }
+
     /// Parse a list of member syntax nodes into a list of `Member`.
     pub(crate) fn parse_members(
         db: &SimpleParserDatabase,
@@ -89,6 +188,9 @@ impl DojoParser {
                         .trim()
                         .to_string(),
                     key: is_key,
+                    skip: member_ast.has_attr(db, "skip"),
+                    default: Self::parse_member_default_value(db, member_ast, diagnostics),
+                    bits: Self::parse_member_bits(db, member_ast, diagnostics),
                 };
 
                 // Make sure all keys are before values in the model.
@@ -108,6 +210,63 @@ impl DojoParser {
             .collect::<Vec<_>>()
     }
 
+    /// Parse the expression of the `#[default(...)]` attribute of a member, if any.
+    pub(crate) fn parse_member_default_value(
+        db: &SimpleParserDatabase,
+        member_ast: &MemberAst,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Option<String> {
+        let attr = member_ast.find_attr(db, "default")?;
+        let args = attr.structurize(db).args;
+
+        if let [arg] = &args[..] {
+            if let AttributeArgVariant::Unnamed(expr) = &arg.variant {
+                return Some(expr.as_syntax_node().get_text_without_trivia(db));
+            }
+        }
+
+        diagnostics.push_error(format!(
+            "The default attribute of the member '{}' expects exactly one value \
+            (i.e #[default(10)]).",
+            member_ast.name(db).text(db)
+        ));
+
+        None
+    }
+
+    /// Parse the bit width of the `#[bits(N)]` attribute of a member, if any.
+    pub(crate) fn parse_member_bits(
+        db: &SimpleParserDatabase,
+        member_ast: &MemberAst,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Option<u8> {
+        let attr = member_ast.find_attr(db, "bits")?;
+        let args = attr.structurize(db).args;
+
+        if let [arg] = &args[..] {
+            if let AttributeArgVariant::Unnamed(expr) = &arg.variant {
+                let bits = expr
+                    .as_syntax_node()
+                    .get_text_without_trivia(db)
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|bits| (1..=MAX_MEMBER_BITS).contains(bits));
+
+                if bits.is_some() {
+                    return bits;
+                }
+            }
+        }
+
+        diagnostics.push_error(format!(
+            "The bits attribute of the member '{}' expects exactly one bit width between 1 \
+            and {MAX_MEMBER_BITS} (i.e #[bits(4)]).",
+            member_ast.name(db).text(db)
+        ));
+
+        None
+    }
+
     /// Extracts the names of the derive attributes from the given attributes.
     ///
     /// # Examples
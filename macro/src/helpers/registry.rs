@@ -0,0 +1,221 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use crate::constants::DOJO_CHECK_TAGS_ENV;
+
+/// Dojo resources discovered by the macros during the expansion.
+#[derive(Debug, Default)]
+struct Registry {
+    // models, events, contracts and libraries, which are registered by name,
+    // as their namespace is only known at deployment time.
+    names: HashSet<String>,
+    // resources with a namespace known at expansion time (i.e contracts
+    // with a `namespace` argument).
+    tags: HashSet<String>,
+    namespaces: HashSet<String>,
+}
+
+/// Registry shared by all the macro invocations done by the macro server,
+/// used to check the tags provided to `selector_from_tag!`.
+///
+/// As the expansion order of the project items is not guaranteed, a tag may be
+/// checked before its resource is registered. That's why this check is opt-in,
+/// enabled by the DOJO_CHECK_TAGS environment variable, and only reports warnings.
+fn registry() -> &'static Mutex<Registry> {
+    static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        Mutex::new(Registry {
+            namespaces: read_config_namespaces(),
+            ..Default::default()
+        })
+    })
+}
+
+pub fn is_enabled() -> bool {
+    std::env::var(DOJO_CHECK_TAGS_ENV).is_ok()
+}
+
+/// Register a resource name (model, event, contract or library).
+pub fn register_name(name: &str) {
+    if !is_enabled() {
+        return;
+    }
+
+    if let Ok(mut registry) = registry().lock() {
+        registry.names.insert(name.to_string());
+    }
+}
+
+/// Register a resource with a namespace known at expansion time.
+pub fn register_tag(namespace: &str, name: &str) {
+    if !is_enabled() {
+        return;
+    }
+
+    if let Ok(mut registry) = registry().lock() {
+        registry.namespaces.insert(namespace.to_string());
+        registry.names.insert(name.to_string());
+        registry.tags.insert(format!("{namespace}-{name}"));
+    }
+}
+
+/// Check a `namespace-name` tag against the registered resources and namespaces.
+/// Returns a message describing the problem, with a suggestion for near matches.
+pub fn check_tag(tag: &str) -> Option<String> {
+    if !is_enabled() {
+        return None;
+    }
+
+    let (namespace, name) = tag.split_once('-')?;
+    let registry = registry().lock().ok()?;
+
+    if registry.tags.contains(tag) {
+        return None;
+    }
+
+    if !registry.namespaces.is_empty() && !registry.namespaces.contains(namespace) {
+        return Some(format!(
+            "The namespace '{namespace}' of the tag '{tag}' is unknown.{}",
+            did_you_mean(namespace, &registry.namespaces)
+        ));
+    }
+
+    if !registry.names.contains(name) {
+        return Some(format!(
+            "No model, event, contract or library named '{name}' has been found for the \
+            tag '{tag}'.{}",
+            did_you_mean(name, &registry.names)
+        ));
+    }
+
+    None
+}
+
+/// Build a suggestion with the closest candidate, if it is close enough.
+fn did_you_mean(value: &str, candidates: &HashSet<String>) -> String {
+    let max_distance = (value.len() / 3).max(2);
+
+    candidates
+        .iter()
+        .map(|c| (levenshtein(value, c), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, c)| format!(" Did you mean '{c}'?"))
+        .unwrap_or_default()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Read the namespaces declared in the Dojo configuration file of the current
+/// profile (`dojo_<profile>.toml`), next to the Scarb manifest.
+fn read_config_namespaces() -> HashSet<String> {
+    let profile = std::env::var("SCARB_PROFILE").unwrap_or_else(|_| "dev".to_string());
+    let dir = std::env::var("SCARB_MANIFEST_PATH")
+        .ok()
+        .and_then(|p| PathBuf::from(p).parent().map(|d| d.to_path_buf()))
+        .unwrap_or_default();
+
+    std::fs::read_to_string(dir.join(format!("dojo_{profile}.toml")))
+        .map(|content| parse_config_namespaces(&content))
+        .unwrap_or_default()
+}
+
+/// Extract the default namespace and the mapped namespaces of a Dojo configuration:
+///
+/// ```toml
+/// [namespace]
+/// default = "ns"
+///
+/// [namespace.mappings]
+/// ns2 = ["ns2-Position"]
+/// ```
+fn parse_config_namespaces(content: &str) -> HashSet<String> {
+    let mut namespaces = HashSet::new();
+    let mut section = "";
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line;
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        let key = key.trim().trim_matches('"');
+
+        match section {
+            "[namespace]" if key == "default" => {
+                namespaces.insert(value.trim().trim_matches('"').to_string());
+            }
+            "[namespace.mappings]" => {
+                namespaces.insert(key.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    namespaces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("Position", "Position"), 0);
+        assert_eq!(levenshtein("Postion", "Position"), 1);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("Moves", "Position"), 7);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let candidates = HashSet::from(["Position".to_string(), "Moves".to_string()]);
+
+        assert_eq!(
+            did_you_mean("Postion", &candidates),
+            " Did you mean 'Position'?"
+        );
+        assert_eq!(did_you_mean("Player", &candidates), "");
+    }
+
+    #[test]
+    fn test_parse_config_namespaces() {
+        let content = "
+        [world]
+        name = \"world\"
+
+        [namespace]
+        default = \"ns\"
+
+        [namespace.mappings]
+        ns2 = [\"ns2-Position\"]
+        \"ns3\" = [\"ns3-Moves\"]
+        ";
+
+        assert_eq!(
+            parse_config_namespaces(content),
+            HashSet::from(["ns".to_string(), "ns2".to_string(), "ns3".to_string()])
+        );
+    }
+}
@@ -2,6 +2,8 @@ use cairo_lang_macro::{quote, TextSpan, Token, TokenStream, TokenTree};
 use cairo_lang_parser::utils::SimpleParserDatabase;
 use cairo_lang_syntax::node::{ast, with_db::SyntaxNodeWithDb, TypedSyntaxNode};
 
+use crate::constants::DOJO_MEMBER_ATTRS;
+
 pub struct DojoTokenizer {}
 
 /// DojoTokenizer provides some functions to build TokenStream or TokenTree.
@@ -18,6 +20,8 @@ impl DojoTokenizer {
     /// in the input token stream.
     /// To avoid processing them a second time, they have to be removed from the original
     /// element.
+    /// Dojo member attributes (`#[skip]`, `#[bits(N)]`) are also removed, as they are
+    /// unknown to the Cairo compiler once the struct has been expanded.
     pub fn rebuild_original_struct(
         db: &SimpleParserDatabase,
         struct_ast: &ast::ItemStruct,
@@ -31,8 +35,14 @@ impl DojoTokenizer {
         let el = struct_ast.generic_params(db).as_syntax_node();
         let generics = SyntaxNodeWithDb::new(&el, db);
 
-        let el = struct_ast.members(db).as_syntax_node();
-        let members = SyntaxNodeWithDb::new(&el, db);
+        let mut members = TokenStream::new(vec![]);
+        members.extend(
+            struct_ast
+                .members(db)
+                .elements(db)
+                .iter()
+                .map(|member| Self::rebuild_member(db, member)),
+        );
 
         quote! {
             #visibility struct #name<#generics> {
@@ -40,4 +50,37 @@ impl DojoTokenizer {
             }
         }
     }
+
+    fn rebuild_member(db: &SimpleParserDatabase, member: &ast::Member) -> TokenStream {
+        let mut attrs = TokenStream::new(vec![]);
+        attrs.extend(
+            member
+                .attributes(db)
+                .elements(db)
+                .iter()
+                .filter(|attr| {
+                    let name = attr.attr(db).as_syntax_node().get_text_without_trivia(db);
+                    !DOJO_MEMBER_ATTRS.contains(&name.as_str())
+                })
+                .map(|attr| {
+                    let el = attr.as_syntax_node();
+                    let attr = SyntaxNodeWithDb::new(&el, db);
+                    quote! { #attr }
+                }),
+        );
+
+        let el = member.visibility(db).as_syntax_node();
+        let visibility = SyntaxNodeWithDb::new(&el, db);
+
+        let el = member.name(db).as_syntax_node();
+        let name = SyntaxNodeWithDb::new(&el, db);
+
+        let el = member.type_clause(db).as_syntax_node();
+        let type_clause = SyntaxNodeWithDb::new(&el, db);
+
+        quote! {
+            #attrs
+            #visibility #name #type_clause,
+        }
+    }
 }
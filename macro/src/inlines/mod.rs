@@ -1,7 +1,10 @@
 use cairo_lang_macro::{inline_macro, ProcMacroResult, TokenStream};
 
 mod bytearray_hash;
+mod namespace_hash;
+mod selector_from_names;
 mod selector_from_tag;
+mod short_string;
 
 #[inline_macro]
 pub fn bytearray_hash(token_stream: TokenStream) -> ProcMacroResult {
@@ -12,3 +15,18 @@ pub fn bytearray_hash(token_stream: TokenStream) -> ProcMacroResult {
 pub fn selector_from_tag(token_stream: TokenStream) -> ProcMacroResult {
     selector_from_tag::process(token_stream)
 }
+
+#[inline_macro]
+pub fn selector_from_names(token_stream: TokenStream) -> ProcMacroResult {
+    selector_from_names::process(token_stream)
+}
+
+#[inline_macro]
+pub fn namespace_hash(token_stream: TokenStream) -> ProcMacroResult {
+    namespace_hash::process(token_stream)
+}
+
+#[inline_macro]
+pub fn short_string(token_stream: TokenStream) -> ProcMacroResult {
+    short_string::process(token_stream)
+}
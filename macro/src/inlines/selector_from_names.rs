@@ -0,0 +1,117 @@
+use cairo_lang_macro::{quote, ProcMacroResult, TokenStream};
+use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::node::{ast, db::SyntaxGroup, Terminal};
+
+use dojo_types::naming;
+
+use crate::helpers::{DojoParser, DojoTokenizer, ProcMacroResultExt};
+
+pub(crate) fn process(token_stream: TokenStream) -> ProcMacroResult {
+    let db = SimpleParserDatabase::default();
+
+    if let Some(args) = DojoParser::parse_inline_args_list(&db, &token_stream) {
+        return process_ast(&db, &args, &token_stream);
+    }
+
+    ProcMacroResult::fail(format!(
+        "selector_from_names: invalid parameter (arg: {token_stream})"
+    ))
+}
+
+fn process_ast(
+    db: &dyn SyntaxGroup,
+    args: &[ast::Expr],
+    token_stream: &TokenStream,
+) -> ProcMacroResult {
+    if let [ast::Expr::String(namespace), ast::Expr::String(name)] = args {
+        let namespace = namespace.text(db).to_string().replace("\"", "");
+        let name = name.text(db).to_string().replace("\"", "");
+
+        if !naming::is_name_valid(&namespace) || !naming::is_name_valid(&name) {
+            return ProcMacroResult::fail(
+                "selector_from_names: Invalid names. Namespace and name can only contain \
+                characters (a-z/A-Z), digits (0-9) and underscore (_)."
+                    .to_string(),
+            );
+        }
+
+        let selector = naming::compute_selector_from_names(&namespace, &name);
+        let selector = format!("{:#64x}", selector);
+
+        let token = DojoTokenizer::tokenize(&selector);
+        return ProcMacroResult::new(quote! { #token });
+    }
+
+    ProcMacroResult::fail(format!(
+        "selector_from_names: invalid parameter types, expected a namespace and a name \
+        (arg: {token_stream})"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_lang_macro::{Severity, TokenStream};
+
+    #[test]
+    fn test_with_bad_inputs() {
+        // input without parenthesis
+        let input = "hello";
+        let res = process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+
+        assert_eq!(res.diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "selector_from_names: invalid parameter (arg: hello)".to_string()
+        );
+
+        // bad input types
+        let input = "(\"ns\", 1234)";
+        let res = process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+
+        assert_eq!(res.diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "selector_from_names: invalid parameter types, expected a namespace and a name \
+            (arg: (\"ns\", 1234))"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_with_invalid_names() {
+        let input = "(\"ns\", \"Posi-tion\")";
+
+        let res = process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+
+        assert_eq!(res.diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "selector_from_names: Invalid names. Namespace and name can only contain \
+            characters (a-z/A-Z), digits (0-9) and underscore (_)."
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_with_valid_names() {
+        let input = "(\"ns\", \"Position\")";
+
+        // same selector as `selector_from_tag!("ns-Position")`.
+        let expected = "0x5e12c61e9cf30881c126a6d298975c8d79f95abed1a05c2d38b7803ed19445f";
+
+        let res = process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 0);
+        assert_eq!(
+            res.token_stream,
+            TokenStream::new(vec![DojoTokenizer::tokenize(expected)])
+        );
+    }
+}
@@ -4,7 +4,7 @@ use cairo_lang_syntax::node::{ast, db::SyntaxGroup, Terminal, TypedSyntaxNode};
 
 use dojo_types::naming;
 
-use crate::helpers::{DojoParser, DojoTokenizer, ProcMacroResultExt};
+use crate::helpers::{registry, DiagnosticsExt, DojoParser, DojoTokenizer, ProcMacroResultExt};
 
 pub(crate) fn process(token_stream: TokenStream) -> ProcMacroResult {
     let db = SimpleParserDatabase::default();
@@ -32,8 +32,14 @@ fn process_ast(db: &dyn SyntaxGroup, expr: &ast::ExprParenthesized) -> ProcMacro
         let selector = naming::compute_selector_from_tag(&tag);
         let selector = format!("{:#64x}", selector);
 
+        let mut diagnostics = vec![];
+
+        if let Some(message) = registry::check_tag(&tag) {
+            diagnostics.push_warning(format!("selector_from_tag: {message}"));
+        }
+
         let token = DojoTokenizer::tokenize(&selector);
-        return ProcMacroResult::new(quote! { #token });
+        return ProcMacroResult::finalize(quote! { #token }, diagnostics);
     }
 
     ProcMacroResult::fail(format!(
@@ -0,0 +1,108 @@
+use cairo_lang_macro::{quote, ProcMacroResult, TokenStream};
+use cairo_lang_parser::utils::SimpleParserDatabase;
+use cairo_lang_syntax::node::{ast, db::SyntaxGroup, Terminal, TypedSyntaxNode};
+use starknet::core::utils::{cairo_short_string_to_felt, CairoShortStringToFeltError};
+
+use crate::helpers::{DojoParser, DojoTokenizer, ProcMacroResultExt};
+
+pub(crate) fn process(token_stream: TokenStream) -> ProcMacroResult {
+    let db = SimpleParserDatabase::default();
+
+    if let Some(expr) = DojoParser::parse_inline_args(&db, &token_stream) {
+        return process_ast(&db, &expr);
+    }
+
+    ProcMacroResult::fail(format!(
+        "short_string: invalid parameter (arg: {token_stream})"
+    ))
+}
+
+fn process_ast(db: &dyn SyntaxGroup, expr: &ast::ExprParenthesized) -> ProcMacroResult {
+    if let ast::Expr::String(s) = expr.expr(db) {
+        let input = s.text(db).to_string().replace("\"", "");
+
+        return match cairo_short_string_to_felt(&input) {
+            Ok(value) => {
+                let token = DojoTokenizer::tokenize(&format!("{:#x}", value));
+                ProcMacroResult::new(quote! { #token })
+            }
+            Err(CairoShortStringToFeltError::NonAsciiCharacter) => ProcMacroResult::fail(format!(
+                "short_string: '{input}' contains non-ASCII characters."
+            )),
+            Err(CairoShortStringToFeltError::StringTooLong) => ProcMacroResult::fail(format!(
+                "short_string: '{input}' is too long ({} bytes). A short string can contain \
+                up to 31 characters.",
+                input.len()
+            )),
+        };
+    }
+
+    ProcMacroResult::fail(format!(
+        "short_string: invalid parameter type (arg: {})",
+        expr.as_syntax_node().get_text(db)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_lang_macro::{Severity, TokenStream};
+
+    #[test]
+    fn test_with_bad_inputs() {
+        // input without parenthesis
+        let input = "hello";
+        let res = process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+
+        assert_eq!(res.diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "short_string: invalid parameter (arg: hello)".to_string()
+        );
+
+        // bad input type
+        let input = "(1234)";
+        let res = process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+
+        assert_eq!(res.diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "short_string: invalid parameter type (arg: (1234))".to_string()
+        );
+    }
+
+    #[test]
+    fn test_with_too_long_string() {
+        let input = "(\"abcdefghijklmnopqrstuvwxyz012345\")";
+
+        let res = process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 1);
+
+        assert_eq!(res.diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            res.diagnostics[0].message,
+            "short_string: 'abcdefghijklmnopqrstuvwxyz012345' is too long (32 bytes). \
+            A short string can contain up to 31 characters."
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_with_valid_input() {
+        let input = "(\"hello\")";
+        let expected = "0x68656c6c6f";
+
+        let res = process(TokenStream::new(vec![DojoTokenizer::tokenize(input)]));
+
+        assert_eq!(res.diagnostics.len(), 0);
+        assert_eq!(
+            res.token_stream,
+            TokenStream::new(vec![DojoTokenizer::tokenize(expected)])
+        );
+    }
+}
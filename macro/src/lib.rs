@@ -3,3 +3,4 @@ mod constants;
 mod derives;
 mod helpers;
 mod inlines;
+mod manifest;
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use cairo_lang_macro::{post_process, PostProcessContext};
+use dojo_types::system::ContractManifest;
+
+use crate::constants::DOJO_MANIFEST_FILE;
+
+/// Collect the contract manifests exported by the `dojo::contract` attribute
+/// (as auxiliary data) and write them into the target directory, so deployment
+/// tools can read them without parsing Cairo code.
+#[post_process]
+pub fn write_manifest(context: PostProcessContext) {
+    let contracts = context
+        .aux_data
+        .into_iter()
+        .filter_map(|data| serde_json::from_slice::<ContractManifest>(&Vec::<u8>::from(data)).ok())
+        .collect::<Vec<_>>();
+
+    if contracts.is_empty() {
+        return;
+    }
+
+    let path = manifest_path();
+
+    // Post processing hooks cannot report diagnostics, and the build itself
+    // does not depend on the manifest: deployment tools report a missing manifest.
+    let _ = serde_json::to_string_pretty(&contracts)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, content)?;
+            Ok(())
+        });
+}
+
+/// Build the path of the manifest file, using the target directory
+/// and the profile of the current Scarb build.
+fn manifest_path() -> PathBuf {
+    let target_dir = std::env::var("SCARB_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let profile = std::env::var("SCARB_PROFILE").unwrap_or_else(|_| "dev".to_string());
+
+    PathBuf::from(target_dir)
+        .join(profile)
+        .join(DOJO_MANIFEST_FILE)
+}
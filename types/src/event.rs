@@ -7,6 +7,13 @@ pub struct WorldSpawned {
     pub caller: Felt,
 }
 
+/// The event emitted when a namespace is registered to a World.
+#[derive(Clone, Debug)]
+pub struct NamespaceRegistered {
+    pub namespace: String,
+    pub hash: Felt,
+}
+
 /// The event emitted when a model is registered to a World.
 #[derive(Clone, Debug)]
 pub struct ModelRegistered {
@@ -14,7 +21,7 @@ pub struct ModelRegistered {
     pub class_hash: Felt,
 }
 
-/// The event emmitted when a model value of an entity is set.
+/// The event emitted when a model value of an entity is set.
 #[derive(Clone, Debug)]
 pub struct StoreSetRecord {
     pub table_id: Felt,
@@ -23,9 +30,72 @@ pub struct StoreSetRecord {
     pub value: Vec<Felt>,
 }
 
-/// The event emmitted when a model is deleted from an entity.
+/// The event emitted when a model is deleted from an entity.
 #[derive(Clone, Debug)]
 pub struct StoreDelRecord {
     pub table_id: Felt,
     pub keys: Vec<Felt>,
 }
+
+/// The `StoreSetRecord` event emitted by the World contract when a model of an
+/// entity is set.
+#[derive(Clone, Debug)]
+pub struct WorldStoreSetRecord {
+    pub selector: Felt,
+    pub entity_id: Felt,
+    pub keys: Vec<Felt>,
+    pub values: Vec<Felt>,
+}
+
+/// The event emitted when the values of a model of an entity are updated.
+#[derive(Clone, Debug)]
+pub struct StoreUpdateRecord {
+    pub selector: Felt,
+    pub entity_id: Felt,
+    pub values: Vec<Felt>,
+}
+
+/// The event emitted when a single member of a model of an entity is updated.
+#[derive(Clone, Debug)]
+pub struct StoreUpdateMember {
+    pub selector: Felt,
+    pub entity_id: Felt,
+    pub member_selector: Felt,
+    pub values: Vec<Felt>,
+}
+
+/// The `StoreDelRecord` event emitted by the World contract when a model is deleted
+/// from an entity. The keys of the entity are not part of the event.
+#[derive(Clone, Debug)]
+pub struct WorldStoreDelRecord {
+    pub selector: Felt,
+    pub entity_id: Felt,
+}
+
+/// The event emitted when a writer permission of a resource is granted or revoked.
+#[derive(Clone, Debug)]
+pub struct WriterUpdated {
+    pub resource: Felt,
+    pub contract: Felt,
+    pub value: bool,
+}
+
+/// The event emitted when an owner permission of a resource is granted or revoked.
+#[derive(Clone, Debug)]
+pub struct OwnerUpdated {
+    pub resource: Felt,
+    pub contract: Felt,
+    pub value: bool,
+}
+
+/// The World events updating the World state.
+#[derive(Clone, Debug)]
+pub enum WorldEvent {
+    NamespaceRegistered(NamespaceRegistered),
+    StoreSetRecord(WorldStoreSetRecord),
+    StoreUpdateRecord(StoreUpdateRecord),
+    StoreUpdateMember(StoreUpdateMember),
+    StoreDelRecord(WorldStoreDelRecord),
+    WriterUpdated(WriterUpdated),
+    OwnerUpdated(OwnerUpdated),
+}
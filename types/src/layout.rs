@@ -0,0 +1,171 @@
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+
+use crate::packing::ParseError;
+
+/// The layout of a field of a struct, or of a variant of an enum, mirroring
+/// `dojo::meta::FieldLayout`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldLayout {
+    /// The member selector for a struct field, or the variant index for an enum variant.
+    pub selector: Felt,
+    pub layout: Layout,
+}
+
+/// The storage layout of a type, mirroring `dojo::meta::Layout`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Layout {
+    /// The bit widths of the values packed together in storage.
+    Fixed(Vec<u8>),
+    Struct(Vec<FieldLayout>),
+    Tuple(Vec<Layout>),
+    /// The layout of the array items.
+    Array(Box<Layout>),
+    ByteArray,
+    Enum(Vec<FieldLayout>),
+}
+
+impl Layout {
+    /// Deserializes a layout serialized with the Cairo `Serde` of `dojo::meta::Layout`,
+    /// like the one stored in [crate::schema::ModelMetadata::layout].
+    pub fn from_felts(felts: &[Felt]) -> Result<Self, ParseError> {
+        let mut felts = felts.iter();
+        let layout = Self::parse(&mut felts)?;
+
+        if felts.next().is_some() {
+            return Err(ParseError::invalid_schema_with_msg(
+                "Unexpected data after the layout.",
+            ));
+        }
+
+        Ok(layout)
+    }
+
+    /// Returns true if the layout has no array, byte array or enum, so the
+    /// storage used by a value does not depend on the value itself.
+    pub fn is_fixed(&self) -> bool {
+        match self {
+            Layout::Fixed(_) => true,
+            Layout::Struct(fields) => fields.iter().all(|f| f.layout.is_fixed()),
+            Layout::Tuple(items) => items.iter().all(Layout::is_fixed),
+            Layout::Array(_) | Layout::ByteArray | Layout::Enum(_) => false,
+        }
+    }
+
+    fn parse<'a>(felts: &mut impl Iterator<Item = &'a Felt>) -> Result<Self, ParseError> {
+        let layout = match next_usize(felts)? {
+            0 => {
+                let len = next_usize(felts)?;
+                let mut widths = Vec::with_capacity(len);
+
+                for _ in 0..len {
+                    let width = next_felt(felts)?.to_u8().ok_or_else(|| {
+                        ParseError::invalid_schema_with_msg("Invalid fixed layout bit width.")
+                    })?;
+                    widths.push(width);
+                }
+
+                Layout::Fixed(widths)
+            }
+            1 => Layout::Struct(Self::parse_field_layouts(felts)?),
+            2 => {
+                let len = next_usize(felts)?;
+                Layout::Tuple(
+                    (0..len)
+                        .map(|_| Self::parse(felts))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            3 => {
+                // the item layout is stored in a span of one layout.
+                if next_usize(felts)? != 1 {
+                    return Err(ParseError::invalid_schema_with_msg(
+                        "An array layout expects exactly one item layout.",
+                    ));
+                }
+
+                Layout::Array(Box::new(Self::parse(felts)?))
+            }
+            4 => Layout::ByteArray,
+            5 => Layout::Enum(Self::parse_field_layouts(felts)?),
+            variant => {
+                return Err(ParseError::invalid_schema_with_msg(&format!(
+                    "Unknown layout variant {variant}."
+                )));
+            }
+        };
+
+        Ok(layout)
+    }
+
+    fn parse_field_layouts<'a>(
+        felts: &mut impl Iterator<Item = &'a Felt>,
+    ) -> Result<Vec<FieldLayout>, ParseError> {
+        let len = next_usize(felts)?;
+
+        (0..len)
+            .map(|_| {
+                let selector = *next_felt(felts)?;
+                Ok(FieldLayout {
+                    selector,
+                    layout: Self::parse(felts)?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn next_felt<'a>(felts: &mut impl Iterator<Item = &'a Felt>) -> Result<&'a Felt, ParseError> {
+    felts
+        .next()
+        .ok_or_else(|| ParseError::invalid_schema_with_msg("Unexpected end of layout."))
+}
+
+fn next_usize<'a>(felts: &mut impl Iterator<Item = &'a Felt>) -> Result<usize, ParseError> {
+    next_felt(felts)?
+        .to_usize()
+        .ok_or_else(|| ParseError::invalid_schema_with_msg("Invalid layout length or variant."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_from_felts() {
+        // Struct([{ 0x12, Fixed([8, 251]) }, { 0x34, Array([Enum([{ 0, Fixed([]) }, { 1,
+        // ByteArray }])]) }])
+        let felts = [1, 2, 0x12, 0, 2, 8, 251, 0x34, 3, 1, 5, 2, 0, 0, 0, 1, 4]
+            .into_iter()
+            .map(Felt::from)
+            .collect::<Vec<_>>();
+
+        let expected = Layout::Struct(vec![
+            FieldLayout {
+                selector: Felt::from(0x12),
+                layout: Layout::Fixed(vec![8, 251]),
+            },
+            FieldLayout {
+                selector: Felt::from(0x34),
+                layout: Layout::Array(Box::new(Layout::Enum(vec![
+                    FieldLayout {
+                        selector: Felt::ZERO,
+                        layout: Layout::Fixed(vec![]),
+                    },
+                    FieldLayout {
+                        selector: Felt::ONE,
+                        layout: Layout::ByteArray,
+                    },
+                ]))),
+            },
+        ]);
+
+        let layout = Layout::from_felts(&felts).unwrap();
+        assert_eq!(layout, expected);
+        assert!(!layout.is_fixed());
+
+        assert!(Layout::from_felts(&felts[..felts.len() - 1]).is_err());
+        assert!(Layout::from_felts(&[Felt::from(6)]).is_err());
+    }
+}
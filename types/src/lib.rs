@@ -5,13 +5,19 @@ use serde::Serialize;
 use starknet::core::types::Felt;
 
 pub mod event;
+pub mod layout;
 pub mod naming;
 pub mod packing;
+pub mod path;
 pub mod primitive;
 pub mod primitive_conversion;
+pub mod query;
+pub mod replay;
 pub mod schema;
 pub mod storage;
 pub mod system;
+pub mod unique_hash;
+pub mod world;
 
 /// Represents the metadata of a World
 #[derive(Debug, Clone, Serialize, Default)]
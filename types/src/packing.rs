@@ -9,6 +9,7 @@ use starknet::core::utils::{
     ParseCairoShortStringError,
 };
 
+use crate::layout::Layout;
 use crate::primitive::{Primitive, PrimitiveError};
 use crate::schema::{self, EnumOption, Ty};
 
@@ -44,8 +45,14 @@ pub enum PackingError {
     UnpackingEntityError,
     #[error(transparent)]
     Primitive(#[from] PrimitiveError),
+    #[error("Value ({value:#x}) exceeds its layout size of {size} bits")]
+    ValueExceedsLayoutSize { value: Felt, size: u8 },
 }
 
+/// The number of bits used in a packed felt, as some 252 bits arrangements are not
+/// valid felt values.
+pub const PACKING_MAX_BITS: usize = 251;
+
 /// Unpacks a vector of packed values according to a given layout.
 ///
 /// # Arguments
@@ -74,7 +81,7 @@ pub fn unpack(mut packed: Vec<Felt>, layout: Vec<Felt>) -> Result<Vec<Felt>, Pac
             })?;
 
         let size: usize = size.into();
-        let remaining_bits = 251 - offset;
+        let remaining_bits = PACKING_MAX_BITS - offset;
 
         // If there are less remaining bits than the size, move to the next felt for unpacking.
         if remaining_bits < size {
@@ -99,6 +106,360 @@ pub fn unpack(mut packed: Vec<Felt>, layout: Vec<Felt>) -> Result<Vec<Felt>, Pac
     Ok(unpacked)
 }
 
+/// Packs a vector of values according to a given layout, the reverse of [unpack].
+///
+/// # Arguments
+///
+/// * `unpacked` - A vector of Felt values to pack.
+/// * `layout` - A vector of Felt values that describe the bit width of each value.
+///
+/// # Returns
+///
+/// * `Result<Vec<Felt>, PackingError>` - A Result containing a vector of packed Felt values if
+///   successful, or an error if a value does not fit in its layout size.
+pub fn pack(unpacked: Vec<Felt>, layout: Vec<Felt>) -> Result<Vec<Felt>, PackingError> {
+    if unpacked.len() < layout.len() {
+        return Err(PackingError::UnpackingEntityError);
+    }
+
+    let mut packed = vec![];
+    let mut packing = U256::ZERO;
+    let mut offset = 0;
+
+    for (value, size) in unpacked.into_iter().zip(layout) {
+        let size: u8 = size
+            .to_u8()
+            .filter(|s| usize::from(*s) <= PACKING_MAX_BITS)
+            .ok_or_else(|| PrimitiveError::ValueOutOfRange {
+                r#type: type_name::<u8>(),
+                value: size,
+            })?;
+
+        let value_256 = U256::from_be_slice(&value.to_bytes_be());
+
+        // the Cairo packing doesn't check the values, only the members narrowed with
+        // `#[bits(N)]` are range checked when a model is serialized. Values wider than
+        // their layout size would overlap the next values, so they are rejected here.
+        if usize::from(size) < PACKING_MAX_BITS && value_256 >> usize::from(size) != U256::ZERO {
+            return Err(PackingError::ValueExceedsLayoutSize { value, size });
+        }
+
+        let size: usize = size.into();
+
+        // If there are less remaining bits than the size, move to the next felt for packing.
+        if PACKING_MAX_BITS - offset < size {
+            packed.push(u256_to_felt(&packing)?);
+            packing = U256::ZERO;
+            offset = 0;
+        }
+
+        packing |= value_256 << offset;
+        offset += size;
+    }
+
+    packed.push(u256_to_felt(&packing)?);
+
+    Ok(packed)
+}
+
+fn u256_to_felt(value: &U256) -> Result<Felt, PackingError> {
+    Ok(Felt::from_hex(&value.to_string()).map_err(ParseError::FromStr)?)
+}
+
+/// Returns the packed layout of a type (the bit width of each unpacked value).
+///
+/// Key members are not packed and are skipped. The bit width of the members
+/// narrowed with `#[bits(N)]` is not part of the schema, but of the model layout
+/// (see [narrowed_member_bits]).
+pub fn packed_layout(ty: &Ty) -> Result<Vec<u8>, PackingError> {
+    match ty {
+        Ty::Primitive(p) => match p {
+            Primitive::Bool(_) => Ok(vec![1]),
+            Primitive::U8(_) => Ok(vec![8]),
+            Primitive::U16(_) => Ok(vec![16]),
+            Primitive::U32(_) => Ok(vec![32]),
+            Primitive::U64(_) => Ok(vec![64]),
+            Primitive::U128(_) => Ok(vec![128]),
+            Primitive::U256(_) => Ok(vec![128, 128]),
+            Primitive::Felt252(_) | Primitive::ClassHash(_) | Primitive::ContractAddress(_) => {
+                Ok(vec![PACKING_MAX_BITS as u8])
+            }
+            _ => Err(ParseError::invalid_schema_with_msg(&format!(
+                "The primitive `{}` cannot be packed.",
+                p
+            ))
+            .into()),
+        },
+        Ty::Struct(s) => {
+            let mut layout = vec![];
+
+            for member in s.children.iter().filter(|m| !m.key) {
+                layout.extend(packed_layout(&member.ty)?);
+            }
+
+            Ok(layout)
+        }
+        Ty::Tuple(tys) => {
+            let mut layout = vec![];
+
+            for ty in tys {
+                layout.extend(packed_layout(ty)?);
+            }
+
+            Ok(layout)
+        }
+        Ty::Enum(e) => {
+            // the variant selector, followed by the data of the largest variant,
+            // each item having the widest bit width used at its position.
+            let mut layout = vec![8];
+
+            for option in &e.options {
+                for (i, bits) in packed_layout(&option.ty)?.into_iter().enumerate() {
+                    match layout.get_mut(i + 1) {
+                        Some(width) => *width = (*width).max(bits),
+                        None => layout.push(bits),
+                    }
+                }
+            }
+
+            Ok(layout)
+        }
+        Ty::Array(_) | Ty::ByteArray(_) => Err(ParseError::invalid_schema_with_msg(&format!(
+            "The type `{}` has no fixed size and cannot be packed.",
+            ty.name()
+        ))
+        .into()),
+    }
+}
+
+/// Returns the bit width of each member of a model narrowed with `#[bits(N)]`, in
+/// schema order, by comparing the layout of the model with the packed layout of
+/// its schema. Members of models which are not packed are never narrowed.
+pub fn narrowed_member_bits(ty: &Ty, layout: &Layout) -> Result<Vec<Option<u8>>, PackingError> {
+    let Ty::Struct(s) = ty else {
+        return Err(ParseError::invalid_schema_with_msg(&format!(
+            "The type `{}` is not a model.",
+            ty.name()
+        ))
+        .into());
+    };
+
+    let Layout::Fixed(widths) = layout else {
+        return Ok(vec![None; s.children.len()]);
+    };
+
+    let mut widths = widths.iter();
+    let mut bits = vec![];
+
+    for member in &s.children {
+        if member.key {
+            bits.push(None);
+            continue;
+        }
+
+        let member_layout = packed_layout(&member.ty)?;
+        let member_widths = widths
+            .by_ref()
+            .take(member_layout.len())
+            .collect::<Vec<_>>();
+
+        if member_widths.len() != member_layout.len() {
+            return Err(PackingError::UnpackingEntityError);
+        }
+
+        // unsigned integers are packed into a single value, and only the variant
+        // selector of an enum, its first value, is narrowed.
+        bits.push(match (&member.ty, &member_layout[..], &member_widths[..]) {
+            (Ty::Enum(_), [width, ..], [narrowed, ..]) if **narrowed < *width => Some(**narrowed),
+            (_, [width], [narrowed]) if **narrowed < *width => Some(**narrowed),
+            _ => None,
+        });
+    }
+
+    if widths.next().is_some() {
+        return Err(PackingError::UnpackingEntityError);
+    }
+
+    Ok(bits)
+}
+
+/// Returns the number of values used by a type once unpacked, where the data of
+/// the enum variants is padded up to the size of the largest variant.
+///
+/// Key members are not packed and are skipped. Arrays and byte arrays have no
+/// fixed size and cannot be packed.
+pub fn packed_size(ty: &Ty) -> Result<usize, PackingError> {
+    match ty {
+        Ty::Primitive(Primitive::U256(_)) => Ok(2),
+        Ty::Primitive(_) => Ok(1),
+        Ty::Struct(s) => s
+            .children
+            .iter()
+            .filter(|m| !m.key)
+            .map(|m| packed_size(&m.ty))
+            .sum(),
+        Ty::Tuple(tys) => tys.iter().map(packed_size).sum(),
+        Ty::Enum(e) => {
+            let mut max_size = 0;
+
+            for option in &e.options {
+                max_size = max_size.max(packed_size(&option.ty)?);
+            }
+
+            // one value for the variant selector.
+            Ok(1 + max_size)
+        }
+        Ty::Array(_) | Ty::ByteArray(_) => Err(ParseError::invalid_schema_with_msg(&format!(
+            "The type `{}` has no fixed size and cannot be packed.",
+            ty.name()
+        ))
+        .into()),
+    }
+}
+
+/// Deserializes the unpacked values of a packed type, as returned by [unpack].
+///
+/// Unlike [Ty::deserialize], the padding following the data of an enum variant
+/// smaller than the largest variant is skipped. Key members are not packed and
+/// are left untouched.
+pub fn deserialize_packed(ty: &mut Ty, felts: &mut Vec<Felt>) -> Result<(), PackingError> {
+    match ty {
+        Ty::Primitive(p) => p.deserialize(felts)?,
+        Ty::Struct(s) => {
+            for child in s.children.iter_mut().filter(|m| !m.key) {
+                deserialize_packed(&mut child.ty, felts)?;
+            }
+        }
+        Ty::Tuple(tys) => {
+            for ty in tys {
+                deserialize_packed(ty, felts)?;
+            }
+        }
+        Ty::Enum(e) => {
+            if felts.is_empty() {
+                return Err(PrimitiveError::MissingFieldElement.into());
+            }
+
+            let value = felts.remove(0);
+            let selector = value
+                .to_u8()
+                .ok_or_else(|| PrimitiveError::ValueOutOfRange {
+                    r#type: type_name::<u8>(),
+                    value,
+                })?;
+
+            let data_size = packed_size(&Ty::Enum(e.clone()))? - 1;
+            let option = e.options.get_mut(selector as usize).ok_or_else(|| {
+                ParseError::invalid_schema_with_msg(&format!(
+                    "Invalid variant selector `{selector}` for the enum `{}`.",
+                    e.name
+                ))
+            })?;
+
+            let option_size = packed_size(&option.ty)?;
+            deserialize_packed(&mut option.ty, felts)?;
+
+            let padding = data_size - option_size;
+            if felts.len() < padding {
+                return Err(PackingError::UnpackingEntityError);
+            }
+
+            felts.drain(0..padding);
+            e.option = Some(selector);
+        }
+        Ty::Array(_) | Ty::ByteArray(_) => {
+            return Err(ParseError::invalid_schema_with_msg(&format!(
+                "The type `{}` has no fixed size and cannot be packed.",
+                ty.name()
+            ))
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Deserializes the values of a type as stored in the World, according to its layout.
+///
+/// The World pads the data of the enums with a fixed layout up to the size of their
+/// largest variant (see `dojo::storage::padding`), so these values are deserialized
+/// with [deserialize_packed], and the other ones with [Ty::deserialize]. Key members
+/// are not part of the layout and are left untouched.
+pub fn deserialize_padded(
+    ty: &mut Ty,
+    layout: &Layout,
+    felts: &mut Vec<Felt>,
+) -> Result<(), PackingError> {
+    match (layout, ty) {
+        (Layout::Fixed(_), ty) => deserialize_packed(ty, felts)?,
+        (Layout::Struct(fields), Ty::Struct(s)) => {
+            let members = s.children.iter_mut().filter(|m| !m.key);
+
+            for (member, field) in members.zip(fields) {
+                deserialize_padded(&mut member.ty, &field.layout, felts)?;
+            }
+        }
+        (Layout::Tuple(layouts), Ty::Tuple(tys)) => {
+            for (ty, layout) in tys.iter_mut().zip(layouts) {
+                deserialize_padded(ty, layout, felts)?;
+            }
+        }
+        (Layout::Array(item_layout), Ty::Array(items)) => {
+            if felts.is_empty() {
+                return Err(PrimitiveError::MissingFieldElement.into());
+            }
+
+            let value = felts.remove(0);
+            let len = value
+                .to_u32()
+                .ok_or_else(|| PrimitiveError::ValueOutOfRange {
+                    r#type: type_name::<u32>(),
+                    value,
+                })?;
+
+            let item_ty = items.pop().ok_or_else(|| {
+                ParseError::invalid_schema_with_msg("An array type expects an item type.")
+            })?;
+
+            for _ in 0..len {
+                let mut item = item_ty.clone();
+                deserialize_padded(&mut item, item_layout, felts)?;
+                items.push(item);
+            }
+        }
+        (Layout::Enum(variants), Ty::Enum(e)) => {
+            if felts.is_empty() {
+                return Err(PrimitiveError::MissingFieldElement.into());
+            }
+
+            let value = felts.remove(0);
+            let selector = value
+                .to_u8()
+                .ok_or_else(|| PrimitiveError::ValueOutOfRange {
+                    r#type: type_name::<u8>(),
+                    value,
+                })?;
+
+            let (variant, option) = variants
+                .iter()
+                .find(|v| v.selector == value)
+                .zip(e.options.get_mut(selector as usize))
+                .ok_or_else(|| {
+                    ParseError::invalid_schema_with_msg(&format!(
+                        "Invalid variant selector `{selector}` for the enum `{}`.",
+                        e.name
+                    ))
+                })?;
+
+            deserialize_padded(&mut option.ty, &variant.layout, felts)?;
+            e.option = Some(selector);
+        }
+        (_, ty) => ty.deserialize(felts)?,
+    }
+
+    Ok(())
+}
+
 /// Parse a raw schema of a model into a Cairo type, [Ty]
 pub fn parse_ty(data: &[Felt]) -> Result<Ty, ParseError> {
     if data.is_empty() {
@@ -196,6 +557,12 @@ fn parse_struct(data: &[Felt]) -> Result<Ty, ParseError> {
     Ok(Ty::Struct(schema::Struct { name, children }))
 }
 
+/// Parse a member of a struct schema.
+///
+/// Only the `key` attribute is kept. The bit width of a member narrowed with `#[bits(N)]`
+/// is also an attribute, but it is only required to pack the member, which is done with
+/// the model layout: the narrowed width is already part of it, and can be retrieved for
+/// each member with [narrowed_member_bits].
 fn parse_member(data: &[Felt]) -> Result<schema::Member, ParseError> {
     if data.len() < 3 {
         return Err(ParseError::invalid_schema_with_msg(&format!(
@@ -217,6 +584,13 @@ fn parse_member(data: &[Felt]) -> Result<schema::Member, ParseError> {
     let slice_end = slice_start + attributes_len as usize;
     let attributes = &data[slice_start..slice_end];
 
+    // The serialized default value of a member follows the `default` attribute.
+    let default = cairo_short_string_to_felt("default")?;
+    let attributes = match attributes.iter().position(|attr| *attr == default) {
+        Some(position) => &attributes[..position],
+        None => attributes,
+    };
+
     let key = attributes.contains(&cairo_short_string_to_felt("key")?);
     let ty = parse_ty(&data[slice_end..])?;
 
@@ -391,6 +765,216 @@ mod tests {
         );
     }
 
+    fn state_enum() -> Ty {
+        // Idle, Moving: (u32, u32), Attacking: u64
+        Ty::Enum(schema::Enum {
+            name: "State".to_string(),
+            option: None,
+            options: vec![
+                EnumOption {
+                    name: "Idle".to_string(),
+                    ty: Ty::Tuple(vec![]),
+                },
+                EnumOption {
+                    name: "Moving".to_string(),
+                    ty: Ty::Tuple(vec![
+                        Ty::Primitive(Primitive::U32(None)),
+                        Ty::Primitive(Primitive::U32(None)),
+                    ]),
+                },
+                EnumOption {
+                    name: "Attacking".to_string(),
+                    ty: Ty::Primitive(Primitive::U64(None)),
+                },
+            ],
+        })
+    }
+
+    #[test]
+    fn packed_size_of_enum_with_different_sizes() {
+        assert_eq!(packed_size(&state_enum()).unwrap(), 3);
+        assert!(packed_size(&Ty::ByteArray("".to_string())).is_err());
+    }
+
+    #[test]
+    fn deserialize_packed_enum_with_padding() {
+        // Attacking(7) followed by one padding value, then a trailing u8.
+        let mut ty = Ty::Tuple(vec![state_enum(), Ty::Primitive(Primitive::U8(None))]);
+        let mut felts = vec![Felt::from(2), Felt::from(7), Felt::ZERO, Felt::from(5)];
+
+        deserialize_packed(&mut ty, &mut felts).unwrap();
+        assert!(felts.is_empty());
+
+        let tys = ty.as_tuple().unwrap();
+        let state = tys[0].as_enum().unwrap();
+
+        assert_eq!(state.option().unwrap().name, "Attacking");
+        assert_eq!(state.options[2].ty, Ty::Primitive(Primitive::U64(Some(7))));
+        assert_eq!(tys[1], Ty::Primitive(Primitive::U8(Some(5))));
+    }
+
+    #[test]
+    fn deserialize_packed_enum_with_invalid_selector() {
+        let mut ty = state_enum();
+        let mut felts = vec![Felt::from(3), Felt::ZERO, Felt::ZERO];
+
+        assert!(deserialize_packed(&mut ty, &mut felts).is_err());
+    }
+
+    #[test]
+    fn pack_and_unpack_narrow_values() {
+        let layout = vec![Felt::from(4), Felt::from(1), Felt::from(250), Felt::from(8)];
+        let unpacked = vec![Felt::from(10), Felt::ONE, Felt::from(42), Felt::from(255)];
+
+        let packed = pack(unpacked.clone(), layout.clone()).unwrap();
+
+        // 4 + 1 bits, then 250 bits, then 8 bits as 250 + 8 bits exceed a felt.
+        assert_eq!(packed.len(), 3);
+        assert_eq!(unpack(packed, layout).unwrap(), unpacked);
+    }
+
+    #[test]
+    fn pack_value_exceeding_layout_size() {
+        let res = pack(vec![Felt::from(16)], vec![Felt::from(4)]);
+
+        assert!(matches!(
+            res,
+            Err(PackingError::ValueExceedsLayoutSize { size: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn narrowed_member_bits_from_layout() {
+        let ty = Ty::Struct(schema::Struct {
+            name: "Player".to_string(),
+            children: vec![
+                schema::Member {
+                    name: "id".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(None)),
+                    key: true,
+                },
+                schema::Member {
+                    name: "level".to_string(),
+                    ty: Ty::Primitive(Primitive::U8(None)),
+                    key: false,
+                },
+                schema::Member {
+                    name: "state".to_string(),
+                    ty: state_enum(),
+                    key: false,
+                },
+                schema::Member {
+                    name: "alive".to_string(),
+                    ty: Ty::Primitive(Primitive::Bool(None)),
+                    key: false,
+                },
+            ],
+        });
+
+        assert_eq!(packed_layout(&ty).unwrap(), vec![8, 8, 64, 32, 1]);
+
+        let layout = Layout::Fixed(vec![4, 8, 64, 32, 1]);
+        assert_eq!(
+            narrowed_member_bits(&ty, &layout).unwrap(),
+            vec![None, Some(4), None, None]
+        );
+
+        // only the variant selector of an enum is narrowed.
+        let layout = Layout::Fixed(vec![8, 2, 64, 32, 1]);
+        assert_eq!(
+            narrowed_member_bits(&ty, &layout).unwrap(),
+            vec![None, None, Some(2), None]
+        );
+
+        assert_eq!(
+            narrowed_member_bits(&ty, &Layout::Struct(vec![])).unwrap(),
+            vec![None; 4]
+        );
+        assert!(narrowed_member_bits(&ty, &Layout::Fixed(vec![4, 8])).is_err());
+    }
+
+    #[test]
+    fn deserialize_packed_skips_keys() {
+        let mut ty = Ty::Struct(schema::Struct {
+            name: "Fighter".to_string(),
+            children: vec![
+                schema::Member {
+                    name: "id".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(Some(1))),
+                    key: true,
+                },
+                schema::Member {
+                    name: "state".to_string(),
+                    ty: state_enum(),
+                    key: false,
+                },
+            ],
+        });
+
+        assert_eq!(packed_size(&ty).unwrap(), 3);
+
+        let mut felts = vec![Felt::from(2), Felt::from(7), Felt::ZERO];
+        deserialize_packed(&mut ty, &mut felts).unwrap();
+        assert!(felts.is_empty());
+
+        let s = ty.as_struct().unwrap();
+        assert_eq!(s.children[0].ty, Ty::Primitive(Primitive::U32(Some(1))));
+        assert_eq!(
+            s.children[1].ty.as_enum().unwrap().option().unwrap().name,
+            "Attacking"
+        );
+    }
+
+    #[test]
+    fn parse_member_ignores_bits() {
+        let data = [
+            cairo_short_string_to_felt("level").unwrap(),
+            Felt::ONE,
+            cairo_short_string_to_felt("bits:4").unwrap(),
+            Felt::ZERO,
+            cairo_short_string_to_felt("u8").unwrap(),
+        ];
+
+        // the bit width is carried by the model layout, not by the schema.
+        let member = parse_member(&data).unwrap();
+
+        assert!(!member.key);
+        assert_eq!(member.ty, Ty::Primitive(Primitive::U8(None)));
+
+        let model = Ty::Struct(schema::Struct {
+            name: "Player".to_string(),
+            children: vec![member],
+        });
+        let unpacked = vec![Felt::from(9)];
+        let layout = Layout::Fixed(vec![4]);
+
+        assert_eq!(
+            narrowed_member_bits(&model, &layout).unwrap(),
+            vec![Some(4)]
+        );
+        assert!(pack(unpacked, vec![Felt::from(4)]).is_ok());
+        assert!(pack(vec![Felt::from(16)], vec![Felt::from(4)]).is_err());
+    }
+
+    #[test]
+    fn parse_member_with_default_value() {
+        let data = [
+            cairo_short_string_to_felt("level").unwrap(),
+            Felt::from(4),
+            cairo_short_string_to_felt("default").unwrap(),
+            Felt::TWO,
+            Felt::MAX,
+            cairo_short_string_to_felt("key").unwrap(),
+            Felt::ZERO,
+            cairo_short_string_to_felt("u256").unwrap(),
+        ];
+
+        let member = parse_member(&data).unwrap();
+
+        assert!(!member.key);
+        assert_eq!(member.ty, Ty::Primitive(Primitive::U256(None)));
+    }
+
     #[test]
     fn parse_array_with_invalid_value() {
         let data = [Felt::default()];
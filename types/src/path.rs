@@ -0,0 +1,466 @@
+use std::fmt;
+use std::mem::{discriminant, replace};
+
+use crate::schema::Ty;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PathError {
+    #[error("Invalid path `{path}` at position {position}.")]
+    InvalidSyntax { path: String, position: usize },
+    #[error("The member `{0}` doesn't exist.")]
+    NotFound(String),
+    #[error("The index of `{path}` is out of bounds, the array has {len} items.")]
+    IndexOutOfBounds { path: String, len: usize },
+    #[error("The variant of `{path}` is not the selected one ({selected}).")]
+    VariantNotSelected { path: String, selected: String },
+    #[error("`{path}` cannot be accessed in a value of type `{ty}`.")]
+    NotAccessible { path: String, ty: String },
+    #[error("The value of `{path}` must be of type `{expected}`, got `{found}`.")]
+    TypeMismatch {
+        path: String,
+        expected: String,
+        found: String,
+    },
+}
+
+/// A segment of a path to a nested value of a [Ty].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A struct member, or a tuple item when it is a number (i.e `stats` or `0`).
+    Member(String),
+    /// An array item (i.e `[3]`).
+    Index(usize),
+    /// The data of the selected variant of an enum (i.e `::Moving`).
+    Variant(String),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Member(name) => write!(f, ".{name}"),
+            PathSegment::Index(index) => write!(f, "[{index}]"),
+            PathSegment::Variant(name) => write!(f, "::{name}"),
+        }
+    }
+}
+
+/// Parses a path to a nested value of a [Ty], made of struct members and tuple items
+/// separated by `.`, array indexes between brackets and enum variants prefixed by `::`
+/// (i.e `stats.hp`, `inventory[3].kind` or `state::Moving.0`).
+///
+/// An empty path targets the value itself.
+pub fn parse_path(path: &str) -> Result<Vec<PathSegment>, PathError> {
+    let bytes = path.as_bytes();
+    let mut segments = vec![];
+    let mut position = 0;
+
+    let invalid = |position| PathError::InvalidSyntax {
+        path: path.to_string(),
+        position,
+    };
+    let read_ident = |position: usize| {
+        let len = path[position..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(path.len() - position);
+
+        if len == 0 {
+            return Err(invalid(position));
+        }
+
+        Ok(path[position..position + len].to_string())
+    };
+
+    // the first member has no separator.
+    if !path.is_empty() && bytes[0] != b'[' && bytes[0] != b':' {
+        let name = read_ident(0)?;
+        position = name.len();
+        segments.push(PathSegment::Member(name));
+    }
+
+    while position < path.len() {
+        match bytes[position] {
+            b'.' => {
+                let name = read_ident(position + 1)?;
+                position += 1 + name.len();
+                segments.push(PathSegment::Member(name));
+            }
+            b'[' => {
+                let end = path[position..]
+                    .find(']')
+                    .ok_or_else(|| invalid(position))?;
+                let index = path[position + 1..position + end]
+                    .parse::<usize>()
+                    .map_err(|_| invalid(position + 1))?;
+                position += end + 1;
+                segments.push(PathSegment::Index(index));
+            }
+            b':' if path[position..].starts_with("::") => {
+                let name = read_ident(position + 2)?;
+                position += 2 + name.len();
+                segments.push(PathSegment::Variant(name));
+            }
+            _ => return Err(invalid(position)),
+        }
+    }
+
+    Ok(segments)
+}
+
+impl Ty {
+    /// Returns the nested value at the given path (see [parse_path] for the syntax).
+    pub fn get(&self, path: &str) -> Result<&Ty, PathError> {
+        let mut ty = self;
+        let mut current = String::new();
+
+        for segment in parse_path(path)? {
+            push_segment(&mut current, &segment);
+            ty = child(ty, child_position(ty, &segment, &current, false)?);
+        }
+
+        Ok(ty)
+    }
+
+    /// Returns the nested type at the given path of a schema (see [parse_path] for the
+    /// syntax). Unlike [Ty::get], enum variants don't have to be selected, and array
+    /// indexes target the item type.
+    pub fn get_type(&self, path: &str) -> Result<&Ty, PathError> {
+        let mut ty = self;
+        let mut current = String::new();
+
+        for segment in parse_path(path)? {
+            push_segment(&mut current, &segment);
+            ty = child(ty, child_position(ty, &segment, &current, true)?);
+        }
+
+        Ok(ty)
+    }
+
+    /// Returns a mutable reference to the nested value at the given path
+    /// (see [parse_path] for the syntax).
+    pub fn get_mut(&mut self, path: &str) -> Result<&mut Ty, PathError> {
+        let mut ty = self;
+        let mut current = String::new();
+
+        for segment in parse_path(path)? {
+            push_segment(&mut current, &segment);
+            let position = child_position(ty, &segment, &current, false)?;
+            ty = child_mut(ty, position);
+        }
+
+        Ok(ty)
+    }
+
+    /// Replaces the nested value at the given path (see [parse_path] for the syntax)
+    /// and returns the previous one. The new value must have the same type as the
+    /// previous one.
+    pub fn set(&mut self, path: &str, value: Ty) -> Result<Ty, PathError> {
+        let target = self.get_mut(path)?;
+
+        if !has_same_type(target, &value) {
+            return Err(PathError::TypeMismatch {
+                path: path.to_string(),
+                expected: target.name(),
+                found: value.name(),
+            });
+        }
+
+        Ok(replace(target, value))
+    }
+}
+
+fn push_segment(path: &mut String, segment: &PathSegment) {
+    match segment {
+        PathSegment::Member(name) if path.is_empty() => path.push_str(name),
+        _ => path.push_str(&segment.to_string()),
+    }
+}
+
+/// Returns the position of the child targeted by a segment, in the struct members,
+/// tuple items, array items or enum options. In a schema, the array item type is
+/// the only item and any enum option can be targeted.
+fn child_position(
+    ty: &Ty,
+    segment: &PathSegment,
+    path: &str,
+    is_schema: bool,
+) -> Result<usize, PathError> {
+    let not_found = || PathError::NotFound(path.to_string());
+
+    match (ty, segment) {
+        (Ty::Struct(s), PathSegment::Member(name)) => s
+            .children
+            .iter()
+            .position(|m| &m.name == name)
+            .ok_or_else(not_found),
+        (Ty::Tuple(items), PathSegment::Member(index)) => index
+            .parse::<usize>()
+            .ok()
+            .filter(|i| *i < items.len())
+            .ok_or_else(not_found),
+        (Ty::Array(items), PathSegment::Index(_)) if is_schema => {
+            if items.is_empty() {
+                Err(not_found())
+            } else {
+                Ok(0)
+            }
+        }
+        (Ty::Array(items), PathSegment::Index(index)) => {
+            if *index < items.len() {
+                Ok(*index)
+            } else {
+                Err(PathError::IndexOutOfBounds {
+                    path: path.to_string(),
+                    len: items.len(),
+                })
+            }
+        }
+        (Ty::Enum(e), PathSegment::Variant(name)) => {
+            let position = e
+                .options
+                .iter()
+                .position(|o| &o.name == name)
+                .ok_or_else(not_found)?;
+
+            if !is_schema && e.option != Some(position as u8) {
+                return Err(PathError::VariantNotSelected {
+                    path: path.to_string(),
+                    selected: e.option().map_or("none".to_string(), |o| o.name.clone()),
+                });
+            }
+
+            Ok(position)
+        }
+        _ => Err(PathError::NotAccessible {
+            path: path.to_string(),
+            ty: ty.name(),
+        }),
+    }
+}
+
+fn child(ty: &Ty, position: usize) -> &Ty {
+    match ty {
+        Ty::Struct(s) => &s.children[position].ty,
+        Ty::Tuple(items) | Ty::Array(items) => &items[position],
+        Ty::Enum(e) => &e.options[position].ty,
+        Ty::Primitive(_) | Ty::ByteArray(_) => unreachable!("checked by child_position"),
+    }
+}
+
+fn child_mut(ty: &mut Ty, position: usize) -> &mut Ty {
+    match ty {
+        Ty::Struct(s) => &mut s.children[position].ty,
+        Ty::Tuple(items) | Ty::Array(items) => &mut items[position],
+        Ty::Enum(e) => &mut e.options[position].ty,
+        Ty::Primitive(_) | Ty::ByteArray(_) => unreachable!("checked by child_position"),
+    }
+}
+
+/// Returns true if both values have the same type, whatever their values.
+fn has_same_type(a: &Ty, b: &Ty) -> bool {
+    match (a, b) {
+        (Ty::Primitive(a), Ty::Primitive(b)) => discriminant(a) == discriminant(b),
+        (Ty::Struct(a), Ty::Struct(b)) => {
+            a.name == b.name
+                && a.children.len() == b.children.len()
+                && a.children
+                    .iter()
+                    .zip(&b.children)
+                    .all(|(a, b)| a.name == b.name && a.key == b.key && has_same_type(&a.ty, &b.ty))
+        }
+        (Ty::Enum(a), Ty::Enum(b)) => {
+            a.name == b.name
+                && a.options.len() == b.options.len()
+                && a.options
+                    .iter()
+                    .zip(&b.options)
+                    .all(|(a, b)| a.name == b.name && has_same_type(&a.ty, &b.ty))
+        }
+        (Ty::Tuple(a), Ty::Tuple(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| has_same_type(a, b))
+        }
+        // arrays without items have no known item type.
+        (Ty::Array(a), Ty::Array(b)) => match (a.first(), b.first()) {
+            (Some(a), Some(b)) => has_same_type(a, b),
+            _ => true,
+        },
+        (Ty::ByteArray(_), Ty::ByteArray(_)) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitive::Primitive;
+    use crate::schema::{Enum, EnumOption, Member, Struct};
+
+    fn member(name: &str, ty: Ty) -> Member {
+        Member {
+            name: name.to_string(),
+            ty,
+            key: false,
+        }
+    }
+
+    fn u8_ty(value: u8) -> Ty {
+        Ty::Primitive(Primitive::U8(Some(value)))
+    }
+
+    fn item(kind: u8) -> Ty {
+        Ty::Struct(Struct {
+            name: "Item".to_string(),
+            children: vec![member("kind", u8_ty(kind))],
+        })
+    }
+
+    fn player() -> Ty {
+        Ty::Struct(Struct {
+            name: "Player".to_string(),
+            children: vec![
+                member(
+                    "stats",
+                    Ty::Struct(Struct {
+                        name: "Stats".to_string(),
+                        children: vec![member("hp", u8_ty(10))],
+                    }),
+                ),
+                member("inventory", Ty::Array(vec![item(1), item(2)])),
+                member(
+                    "state",
+                    Ty::Enum(Enum {
+                        name: "State".to_string(),
+                        option: Some(1),
+                        options: vec![
+                            EnumOption {
+                                name: "Idle".to_string(),
+                                ty: Ty::Tuple(vec![]),
+                            },
+                            EnumOption {
+                                name: "Moving".to_string(),
+                                ty: Ty::Tuple(vec![u8_ty(3), u8_ty(4)]),
+                            },
+                        ],
+                    }),
+                ),
+            ],
+        })
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("inventory[3].kind").unwrap(),
+            vec![
+                PathSegment::Member("inventory".to_string()),
+                PathSegment::Index(3),
+                PathSegment::Member("kind".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_path("state::Moving.0").unwrap(),
+            vec![
+                PathSegment::Member("state".to_string()),
+                PathSegment::Variant("Moving".to_string()),
+                PathSegment::Member("0".to_string()),
+            ]
+        );
+        assert_eq!(parse_path("").unwrap(), vec![]);
+        assert_eq!(parse_path("[1]").unwrap(), vec![PathSegment::Index(1)]);
+
+        for (path, position) in [
+            ("stats.", 6),
+            ("a[x]", 2),
+            ("a[1", 1),
+            ("a:b", 1),
+            ("a b", 1),
+        ] {
+            assert_eq!(
+                parse_path(path),
+                Err(PathError::InvalidSyntax {
+                    path: path.to_string(),
+                    position
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_get() {
+        let player = player();
+
+        assert_eq!(player.get("stats.hp").unwrap(), &u8_ty(10));
+        assert_eq!(player.get("inventory[1].kind").unwrap(), &u8_ty(2));
+        assert_eq!(player.get("state::Moving.1").unwrap(), &u8_ty(4));
+        assert_eq!(player.get("").unwrap(), &player);
+
+        assert_eq!(
+            player.get("stats.mp"),
+            Err(PathError::NotFound("stats.mp".to_string()))
+        );
+        assert_eq!(
+            player.get("inventory[2].kind"),
+            Err(PathError::IndexOutOfBounds {
+                path: "inventory[2]".to_string(),
+                len: 2
+            })
+        );
+        assert_eq!(
+            player.get("state::Idle"),
+            Err(PathError::VariantNotSelected {
+                path: "state::Idle".to_string(),
+                selected: "Moving".to_string(),
+            })
+        );
+        assert_eq!(
+            player.get("stats[0]"),
+            Err(PathError::NotAccessible {
+                path: "stats[0]".to_string(),
+                ty: "Stats".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_get_type() {
+        let player = player();
+
+        assert_eq!(player.get_type("state::Idle").unwrap(), &Ty::Tuple(vec![]));
+        assert_eq!(player.get_type("inventory[5].kind").unwrap(), &u8_ty(1));
+        assert_eq!(
+            player.get_type("state::Dead"),
+            Err(PathError::NotFound("state::Dead".to_string()))
+        );
+        assert_eq!(
+            Ty::Array(vec![]).get_type("[0]"),
+            Err(PathError::NotFound("[0]".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_get_mut_and_set() {
+        let mut player = player();
+
+        *player.get_mut("stats.hp").unwrap() = u8_ty(20);
+        assert_eq!(player.get("stats.hp").unwrap(), &u8_ty(20));
+
+        let previous = player.set("inventory[0]", item(5)).unwrap();
+        assert_eq!(previous, item(1));
+        assert_eq!(player.get("inventory[0].kind").unwrap(), &u8_ty(5));
+
+        player.set("inventory", Ty::Array(vec![])).unwrap();
+        assert_eq!(player.get("inventory").unwrap(), &Ty::Array(vec![]));
+
+        assert_eq!(
+            player.set("state::Moving.0", Ty::Primitive(Primitive::U16(Some(1)))),
+            Err(PathError::TypeMismatch {
+                path: "state::Moving.0".to_string(),
+                expected: "u8".to_string(),
+                found: "u16".to_string(),
+            })
+        );
+        assert!(matches!(
+            player.set("stats", item(1)),
+            Err(PathError::TypeMismatch { .. })
+        ));
+    }
+}
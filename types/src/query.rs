@@ -0,0 +1,1052 @@
+use std::cmp::Ordering;
+use std::mem::discriminant;
+
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+
+use crate::naming::get_tag;
+use crate::path::PathError;
+use crate::primitive::{Primitive, PrimitiveError, SqlType};
+use crate::schema::{ModelMetadata, Ty};
+use crate::storage::Query;
+
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    #[error("The member path `{0}` doesn't exist.")]
+    MemberNotFound(String),
+    #[error("The value compared to `{0}` doesn't match the member type.")]
+    TypeMismatch(String),
+    #[error("The operator `{operator:?}` cannot be applied to `{path}`.")]
+    UnsupportedOperator {
+        path: String,
+        operator: ComparisonOperator,
+    },
+    #[error("The member `{0}` cannot be used to order entities.")]
+    UnorderedMember(String),
+    #[error("Only model structs can be queried.")]
+    NotAModel,
+    #[error(transparent)]
+    Path(PathError),
+    #[error(transparent)]
+    Primitive(#[from] PrimitiveError),
+}
+
+/// The pattern matching a single key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyPattern {
+    Exact(Felt),
+    Wildcard,
+}
+
+/// Defines if the keys must have exactly as many keys as the pattern, or if
+/// the pattern is only a prefix of the keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatternMatching {
+    FixedLen,
+    VariableLen,
+}
+
+/// Matches the serialized keys of entities.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeysClause {
+    pub keys: Vec<KeyPattern>,
+    pub pattern_matching: PatternMatching,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComparisonOperator {
+    Eq,
+    Neq,
+    Lt,
+    Gt,
+    In,
+}
+
+/// A value compared to an entity member.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MemberValue {
+    Primitive(Primitive),
+    /// An enum variant name or a byte array.
+    String(String),
+    /// The candidate values of the `In` operator.
+    List(Vec<MemberValue>),
+}
+
+/// Compares an entity member, identified by its path (i.e `stats.hp`, `position.0`
+/// for a tuple item or `state::Moving.0` for the data of an enum variant), to a value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberClause {
+    pub path: String,
+    pub operator: ComparisonOperator,
+    pub value: MemberValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Clause {
+    Keys(KeysClause),
+    Member(MemberClause),
+    And(Vec<Clause>),
+    Or(Vec<Clause>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderBy {
+    pub path: String,
+    pub direction: OrderDirection,
+}
+
+/// A query on the entities of a model.
+///
+/// The same query can be evaluated on in-memory entities with [EntityQuery::filter],
+/// or translated to SQL with [EntityQuery::to_sql], with the same semantics: unset
+/// primitives are compared as their default value, as they are stored in SQL, and
+/// `i128` members can't be ordered, as they are stored as two's complement strings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntityQuery {
+    pub clause: Option<Clause>,
+    pub order_by: Vec<OrderBy>,
+    pub limit: Option<u32>,
+    pub offset: u32,
+}
+
+impl From<Query> for EntityQuery {
+    fn from(query: Query) -> Self {
+        Self {
+            clause: Some(Clause::Keys(KeysClause {
+                keys: query.keys.into_iter().map(KeyPattern::Exact).collect(),
+                pattern_matching: PatternMatching::FixedLen,
+            })),
+            ..Default::default()
+        }
+    }
+}
+
+impl EntityQuery {
+    /// Returns the entities matching the query, ordered and paginated. Each entity
+    /// is a model struct with its values.
+    pub fn filter<'a>(
+        &self,
+        entities: impl IntoIterator<Item = &'a Ty>,
+    ) -> Result<Vec<&'a Ty>, QueryError> {
+        let mut matching = vec![];
+
+        for entity in entities {
+            let is_matching = match &self.clause {
+                Some(clause) => clause.matches(entity)?,
+                None => true,
+            };
+
+            if is_matching {
+                matching.push(entity);
+            }
+        }
+
+        // resolve the ordered members first, to report errors instead of panicking while sorting.
+        let mut sort_keys = vec![];
+        for entity in &matching {
+            let members = self
+                .order_by
+                .iter()
+                .map(|o| match resolve_selected(entity, &o.path)? {
+                    Some(ty) => orderable(ty, &o.path).map(Some),
+                    None => Ok(None),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            sort_keys.push(members);
+        }
+
+        let mut indexes = (0..matching.len()).collect::<Vec<_>>();
+        indexes.sort_by(|a, b| {
+            self.order_by
+                .iter()
+                .enumerate()
+                .map(|(i, o)| {
+                    let ordering = sort_keys[*a][i].cmp(&sort_keys[*b][i]);
+                    match o.direction {
+                        OrderDirection::Asc => ordering,
+                        OrderDirection::Desc => ordering.reverse(),
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        Ok(indexes
+            .into_iter()
+            .skip(self.offset as usize)
+            .take(self.limit.map_or(usize::MAX, |limit| limit as usize))
+            .map(|i| matching[i])
+            .collect())
+    }
+
+    /// Translates the query to a SQL query on the table of a model.
+    ///
+    /// The table is named after the model tag, with one column per primitive, enum or
+    /// byte array member, named after the member path (i.e `stats.hp`), and a `keys`
+    /// column holding the serialized keys as hexadecimal strings, each one followed by `/`.
+    /// Values are formatted with [Primitive::to_sql_value], enums are stored as their
+    /// variant name and the data of the variants which are not selected are `NULL`.
+    pub fn to_sql(&self, model: &ModelMetadata) -> Result<String, QueryError> {
+        let mut sql = format!("SELECT * FROM [{}]", get_tag(&model.namespace, &model.name));
+
+        if let Some(clause) = &self.clause {
+            sql.push_str(&format!(" WHERE {}", clause.to_sql(&model.schema)?));
+        }
+
+        if !self.order_by.is_empty() {
+            let order_by = self
+                .order_by
+                .iter()
+                .map(|o| {
+                    orderable(resolve_type(&model.schema, &o.path)?, &o.path)?;
+                    let direction = match o.direction {
+                        OrderDirection::Asc => "ASC",
+                        OrderDirection::Desc => "DESC",
+                    };
+                    Ok(format!("[{}] {direction}", o.path))
+                })
+                .collect::<Result<Vec<_>, QueryError>>()?;
+            sql.push_str(&format!(" ORDER BY {}", order_by.join(", ")));
+        }
+
+        match (self.limit, self.offset) {
+            (Some(limit), offset) => sql.push_str(&format!(" LIMIT {limit} OFFSET {offset}")),
+            // sqlite requires a limit to use an offset.
+            (None, offset) if offset > 0 => sql.push_str(&format!(" LIMIT -1 OFFSET {offset}")),
+            _ => {}
+        }
+
+        Ok(sql)
+    }
+}
+
+impl Clause {
+    /// Returns true if the entity matches the clause.
+    pub fn matches(&self, entity: &Ty) -> Result<bool, QueryError> {
+        match self {
+            Clause::Keys(keys) => Ok(keys.matches(&entity_keys(entity)?)),
+            Clause::Member(member) => member.matches(entity),
+            Clause::And(clauses) => {
+                for clause in clauses {
+                    if !clause.matches(entity)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Clause::Or(clauses) => {
+                for clause in clauses {
+                    if clause.matches(entity)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    fn to_sql(&self, schema: &Ty) -> Result<String, QueryError> {
+        let join = |clauses: &[Clause], separator: &str, empty: &str| {
+            if clauses.is_empty() {
+                return Ok(empty.to_string());
+            }
+
+            let clauses = clauses
+                .iter()
+                .map(|c| c.to_sql(schema))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("({})", clauses.join(separator)))
+        };
+
+        match self {
+            Clause::Keys(keys) => Ok(keys.to_sql()),
+            Clause::Member(member) => member.to_sql(schema),
+            Clause::And(clauses) => join(clauses, " AND ", "TRUE"),
+            Clause::Or(clauses) => join(clauses, " OR ", "FALSE"),
+        }
+    }
+}
+
+impl KeysClause {
+    /// Returns true if the serialized keys match the pattern.
+    pub fn matches(&self, keys: &[Felt]) -> bool {
+        let is_len_valid = match self.pattern_matching {
+            PatternMatching::FixedLen => keys.len() == self.keys.len(),
+            PatternMatching::VariableLen => keys.len() >= self.keys.len(),
+        };
+
+        is_len_valid
+            && self
+                .keys
+                .iter()
+                .zip(keys)
+                .all(|(pattern, key)| match pattern {
+                    KeyPattern::Exact(expected) => expected == key,
+                    KeyPattern::Wildcard => true,
+                })
+    }
+
+    fn to_sql(&self) -> String {
+        let mut regex = self
+            .keys
+            .iter()
+            .map(|k| match k {
+                KeyPattern::Exact(key) => format!("{key:#x}/"),
+                KeyPattern::Wildcard => "0x[0-9a-f]+/".to_string(),
+            })
+            .collect::<String>();
+
+        if self.pattern_matching == PatternMatching::VariableLen {
+            regex.push_str("(0x[0-9a-f]+/)*");
+        }
+
+        format!("[keys] REGEXP '^{regex}$'")
+    }
+}
+
+impl MemberClause {
+    /// Returns true if the member of the entity matches the clause.
+    pub fn matches(&self, entity: &Ty) -> Result<bool, QueryError> {
+        // like `NULL` in SQL, the data of a variant which is not selected matches nothing.
+        let member = match resolve_selected(entity, &self.path)? {
+            Some(member) => comparable(member, &self.path)?,
+            None => return Ok(false),
+        };
+
+        let compare = |value: &MemberValue| -> Result<Ordering, QueryError> {
+            Ok(member.cmp(&self.comparable_value(value, &member)?))
+        };
+
+        match (self.operator, &self.value) {
+            (ComparisonOperator::In, MemberValue::List(values)) => {
+                for value in values {
+                    if compare(value)?.is_eq() {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            (ComparisonOperator::In, _) | (_, MemberValue::List(_)) => {
+                Err(QueryError::TypeMismatch(self.path.clone()))
+            }
+            (ComparisonOperator::Lt | ComparisonOperator::Gt, _) if !member.is_ordered() => {
+                Err(self.unsupported_operator())
+            }
+            (ComparisonOperator::Eq, value) => Ok(compare(value)?.is_eq()),
+            (ComparisonOperator::Neq, value) => Ok(compare(value)?.is_ne()),
+            (ComparisonOperator::Lt, value) => Ok(compare(value)?.is_lt()),
+            (ComparisonOperator::Gt, value) => Ok(compare(value)?.is_gt()),
+        }
+    }
+
+    fn to_sql(&self, schema: &Ty) -> Result<String, QueryError> {
+        let kind = comparable(resolve_type(schema, &self.path)?, &self.path)?;
+        let column = format!("[{}]", self.path);
+
+        let sql_value = |value: &MemberValue| -> Result<String, QueryError> {
+            self.comparable_value(value, &kind)?;
+
+            Ok(match value {
+                MemberValue::Primitive(p) => match p.to_sql_type() {
+                    SqlType::Integer => p.to_sql_value(),
+                    SqlType::Text => format!("'{}'", p.to_sql_value()),
+                },
+                MemberValue::String(s) => format!("'{}'", s.replace('\'', "''")),
+                MemberValue::List(_) => unreachable!("lists are rejected as compared values"),
+            })
+        };
+
+        match (self.operator, &self.value) {
+            (ComparisonOperator::In, MemberValue::List(values)) => {
+                let values = values
+                    .iter()
+                    .map(sql_value)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(format!("{column} IN ({})", values.join(", ")))
+            }
+            (ComparisonOperator::In, _) | (_, MemberValue::List(_)) => {
+                Err(QueryError::TypeMismatch(self.path.clone()))
+            }
+            (ComparisonOperator::Lt | ComparisonOperator::Gt, _) if !kind.is_ordered() => {
+                Err(self.unsupported_operator())
+            }
+            (operator, value) => {
+                let operator = match operator {
+                    ComparisonOperator::Eq => "=",
+                    ComparisonOperator::Neq => "!=",
+                    ComparisonOperator::Lt => "<",
+                    ComparisonOperator::Gt => ">",
+                    ComparisonOperator::In => unreachable!(),
+                };
+                Ok(format!("{column} {operator} {}", sql_value(value)?))
+            }
+        }
+    }
+
+    /// Converts a compared value to the kind of the member, checking its type.
+    fn comparable_value(
+        &self,
+        value: &MemberValue,
+        member: &Comparable,
+    ) -> Result<Comparable, QueryError> {
+        match (value, member) {
+            (MemberValue::Primitive(value), Comparable::Primitive(member))
+                if discriminant(value) == discriminant(member) =>
+            {
+                Ok(Comparable::Primitive(*value))
+            }
+            (MemberValue::String(value), Comparable::Variant(_)) => {
+                Ok(Comparable::Variant(value.clone()))
+            }
+            (MemberValue::String(value), Comparable::String(_)) => {
+                Ok(Comparable::String(value.clone()))
+            }
+            _ => Err(QueryError::TypeMismatch(self.path.clone())),
+        }
+    }
+
+    fn unsupported_operator(&self) -> QueryError {
+        QueryError::UnsupportedOperator {
+            path: self.path.clone(),
+            operator: self.operator,
+        }
+    }
+}
+
+/// A member value that can be compared, as stored in a SQL column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Comparable {
+    Primitive(Primitive),
+    /// The name of the selected variant of an enum.
+    Variant(String),
+    String(String),
+}
+
+impl Comparable {
+    /// Returns true if the values can be compared with `<` and `>`, the same way in memory
+    /// and in SQL. Enum variants are only compared by name, and `i128` are stored as
+    /// two's complement hexadecimal strings which are not ordered as integers.
+    fn is_ordered(&self) -> bool {
+        !matches!(
+            self,
+            Comparable::Variant(_) | Comparable::Primitive(Primitive::I128(_))
+        )
+    }
+}
+
+impl PartialOrd for Comparable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Comparable {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Comparable::Primitive(a), Comparable::Primitive(b)) => cmp_primitives(a, b),
+            (Comparable::Variant(a), Comparable::Variant(b))
+            | (Comparable::String(a), Comparable::String(b)) => a.cmp(b),
+            // members of a given path always have the same kind.
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+/// Compares primitives of the same type, unset values being compared as their default
+/// value, as written by [Primitive::to_sql_value].
+fn cmp_primitives(a: &Primitive, b: &Primitive) -> Ordering {
+    fn cmp<T: Ord + Default + Copy>(a: &Option<T>, b: &Option<T>) -> Ordering {
+        a.unwrap_or_default().cmp(&b.unwrap_or_default())
+    }
+
+    match (a, b) {
+        (Primitive::I8(a), Primitive::I8(b)) => cmp(a, b),
+        (Primitive::I16(a), Primitive::I16(b)) => cmp(a, b),
+        (Primitive::I32(a), Primitive::I32(b)) => cmp(a, b),
+        (Primitive::I64(a), Primitive::I64(b)) => cmp(a, b),
+        (Primitive::I128(a), Primitive::I128(b)) => cmp(a, b),
+        (Primitive::U8(a), Primitive::U8(b)) => cmp(a, b),
+        (Primitive::U16(a), Primitive::U16(b)) => cmp(a, b),
+        (Primitive::U32(a), Primitive::U32(b)) => cmp(a, b),
+        (Primitive::U64(a), Primitive::U64(b)) => cmp(a, b),
+        (Primitive::U128(a), Primitive::U128(b)) => cmp(a, b),
+        (Primitive::U256(a), Primitive::U256(b)) => cmp(a, b),
+        (Primitive::Bool(a), Primitive::Bool(b)) => cmp(a, b),
+        (Primitive::Felt252(a), Primitive::Felt252(b))
+        | (Primitive::ClassHash(a), Primitive::ClassHash(b))
+        | (Primitive::ContractAddress(a), Primitive::ContractAddress(b))
+        | (Primitive::EthAddress(a), Primitive::EthAddress(b)) => {
+            // compare big-endian bytes to compare felts as integers.
+            let (a, b) = (a.unwrap_or_default(), b.unwrap_or_default());
+            a.to_bytes_be().cmp(&b.to_bytes_be())
+        }
+        _ => Ordering::Equal,
+    }
+}
+
+fn comparable(ty: &Ty, path: &str) -> Result<Comparable, QueryError> {
+    match ty {
+        Ty::Primitive(p) => Ok(Comparable::Primitive(*p)),
+        Ty::Enum(e) => Ok(Comparable::Variant(e.to_sql_value())),
+        Ty::ByteArray(s) => Ok(Comparable::String(s.clone())),
+        Ty::Struct(_) | Ty::Tuple(_) | Ty::Array(_) => {
+            Err(QueryError::MemberNotFound(path.to_string()))
+        }
+    }
+}
+
+/// Returns the comparable value of a member used to order entities.
+fn orderable(ty: &Ty, path: &str) -> Result<Comparable, QueryError> {
+    match comparable(ty, path)? {
+        Comparable::Primitive(Primitive::I128(_)) => {
+            Err(QueryError::UnorderedMember(path.to_string()))
+        }
+        comparable => Ok(comparable),
+    }
+}
+
+/// Resolves a member of an entity from its path (see [crate::path::parse_path]), or
+/// returns `None` if the path goes through the data of a variant which is not selected.
+fn resolve_selected<'a>(entity: &'a Ty, path: &str) -> Result<Option<&'a Ty>, QueryError> {
+    match entity.get(path) {
+        Ok(member) => Ok(Some(member)),
+        Err(PathError::VariantNotSelected { .. }) => Ok(None),
+        Err(e) => Err(path_error(e, path)),
+    }
+}
+
+/// Resolves the type of a member from its path in a model schema.
+fn resolve_type<'a>(schema: &'a Ty, path: &str) -> Result<&'a Ty, QueryError> {
+    schema.get_type(path).map_err(|e| path_error(e, path))
+}
+
+fn path_error(error: PathError, path: &str) -> QueryError {
+    match error {
+        PathError::NotFound(_) => QueryError::MemberNotFound(path.to_string()),
+        e => QueryError::Path(e),
+    }
+}
+
+/// Returns the serialized keys of a model entity.
+fn entity_keys(entity: &Ty) -> Result<Vec<Felt>, QueryError> {
+    let model = entity.as_struct().ok_or(QueryError::NotAModel)?;
+
+    let mut keys = vec![];
+    for member in model.keys() {
+        keys.extend(member.serialize()?);
+    }
+
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use rusqlite::functions::FunctionFlags;
+    use rusqlite::types::Value;
+    use rusqlite::Connection;
+
+    use super::*;
+    use crate::schema::{Enum, EnumOption, Member, Struct};
+
+    fn player(id: u32, name: &str, hp: u8, state: u8) -> Ty {
+        Ty::Struct(Struct {
+            name: "Player".to_string(),
+            children: vec![
+                Member {
+                    name: "id".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(Some(id))),
+                    key: true,
+                },
+                Member {
+                    name: "name".to_string(),
+                    ty: Ty::ByteArray(name.to_string()),
+                    key: false,
+                },
+                Member {
+                    name: "stats".to_string(),
+                    ty: Ty::Struct(Struct {
+                        name: "Stats".to_string(),
+                        children: vec![Member {
+                            name: "hp".to_string(),
+                            ty: Ty::Primitive(Primitive::U8(Some(hp))),
+                            key: false,
+                        }],
+                    }),
+                    key: false,
+                },
+                Member {
+                    name: "state".to_string(),
+                    ty: Ty::Enum(Enum {
+                        name: "State".to_string(),
+                        option: Some(state),
+                        options: vec![
+                            EnumOption {
+                                name: "Idle".to_string(),
+                                ty: Ty::Tuple(vec![]),
+                            },
+                            EnumOption {
+                                name: "Moving".to_string(),
+                                ty: Ty::Tuple(vec![Ty::Primitive(Primitive::U32(Some(id * 10)))]),
+                            },
+                        ],
+                    }),
+                    key: false,
+                },
+            ],
+        })
+    }
+
+    fn players() -> Vec<Ty> {
+        vec![
+            player(1, "alice", 10, 0),
+            player(2, "bob", 30, 1),
+            player(3, "carol", 20, 1),
+        ]
+    }
+
+    fn names(entities: &[&Ty]) -> Vec<String> {
+        entities
+            .iter()
+            .map(|e| {
+                e.as_struct()
+                    .unwrap()
+                    .get("name")
+                    .unwrap()
+                    .as_byte_array()
+                    .unwrap()
+                    .clone()
+            })
+            .collect()
+    }
+
+    fn hp_clause(operator: ComparisonOperator, hp: u8) -> Clause {
+        Clause::Member(MemberClause {
+            path: "stats.hp".to_string(),
+            operator,
+            value: MemberValue::Primitive(Primitive::U8(Some(hp))),
+        })
+    }
+
+    fn model_metadata() -> ModelMetadata {
+        // the enums of a schema have no selected variant.
+        let mut schema = player(0, "", 0, 0);
+        if let Ty::Enum(state) = schema.get_mut("state").unwrap() {
+            state.option = None;
+        }
+
+        ModelMetadata {
+            schema,
+            namespace: "ns".to_string(),
+            name: "Player".to_string(),
+            packed_size: 0,
+            unpacked_size: 0,
+            class_hash: Felt::ZERO,
+            contract_address: Felt::ZERO,
+            layout: vec![],
+        }
+    }
+
+    /// Runs the query on a sqlite table holding the entities, stored as described
+    /// by [EntityQuery::to_sql], and returns the names of the selected entities.
+    fn sql_names(query: &EntityQuery, entities: &[Ty]) -> Vec<String> {
+        let columns = ["name", "stats.hp", "state", "state::Moving.0"];
+
+        let conn = Connection::open_in_memory().unwrap();
+        conn.create_scalar_function(
+            "regexp",
+            2,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx| {
+                let regex = Regex::new(&ctx.get::<String>(0)?)
+                    .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))?;
+                Ok(regex.is_match(&ctx.get::<String>(1)?))
+            },
+        )
+        .unwrap();
+        conn.execute(
+            "CREATE TABLE [ns-Player] ([keys] TEXT, [name] TEXT, [stats.hp] INTEGER, [state] TEXT, \
+             [state::Moving.0] INTEGER)",
+            [],
+        )
+        .unwrap();
+
+        for entity in entities {
+            let keys = entity_keys(entity)
+                .unwrap()
+                .iter()
+                .map(|k| format!("{k:#x}/"))
+                .collect::<String>();
+
+            let mut values = vec![Value::Text(keys)];
+            for column in columns {
+                values.push(match resolve_selected(entity, column).unwrap() {
+                    None => Value::Null,
+                    Some(Ty::Primitive(p)) => match p.to_sql_type() {
+                        SqlType::Integer => Value::Integer(p.to_sql_value().parse().unwrap()),
+                        SqlType::Text => Value::Text(p.to_sql_value()),
+                    },
+                    Some(Ty::Enum(e)) => Value::Text(e.to_sql_value()),
+                    Some(Ty::ByteArray(s)) => Value::Text(s.clone()),
+                    Some(_) => unreachable!(),
+                });
+            }
+
+            conn.execute(
+                "INSERT INTO [ns-Player] VALUES (?, ?, ?, ?, ?)",
+                rusqlite::params_from_iter(values),
+            )
+            .unwrap();
+        }
+
+        let mut statement = conn
+            .prepare(&query.to_sql(&model_metadata()).unwrap())
+            .unwrap();
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>("name"))
+            .unwrap();
+        rows.map(|name| name.unwrap()).collect()
+    }
+
+    #[test]
+    fn test_keys_clause() {
+        let clause = KeysClause {
+            keys: vec![KeyPattern::Wildcard, KeyPattern::Exact(Felt::TWO)],
+            pattern_matching: PatternMatching::FixedLen,
+        };
+
+        assert!(clause.matches(&[Felt::ONE, Felt::TWO]));
+        assert!(!clause.matches(&[Felt::ONE, Felt::ONE]));
+        assert!(!clause.matches(&[Felt::ONE, Felt::TWO, Felt::THREE]));
+
+        let clause = KeysClause {
+            pattern_matching: PatternMatching::VariableLen,
+            ..clause
+        };
+        assert!(clause.matches(&[Felt::ONE, Felt::TWO, Felt::THREE]));
+        assert!(!clause.matches(&[Felt::ONE]));
+
+        let query = EntityQuery::from(Query {
+            address_domain: 0,
+            keys: vec![Felt::TWO],
+        });
+        let entities = players();
+        assert_eq!(names(&query.filter(&entities).unwrap()), vec!["bob"]);
+    }
+
+    #[test]
+    fn test_entity_keys_with_enum_key() {
+        let mut entity = player(2, "bob", 30, 1);
+        if let Ty::Struct(s) = &mut entity {
+            s.children[3].key = true;
+        }
+
+        // only the data of the selected variant is part of the keys.
+        assert_eq!(
+            entity_keys(&entity).unwrap(),
+            vec![Felt::TWO, Felt::ONE, Felt::from(20)]
+        );
+    }
+
+    #[test]
+    fn test_filter_order_and_paginate() {
+        let entities = players();
+
+        let query = EntityQuery {
+            clause: Some(Clause::Or(vec![
+                hp_clause(ComparisonOperator::Gt, 15),
+                Clause::Member(MemberClause {
+                    path: "state".to_string(),
+                    operator: ComparisonOperator::Eq,
+                    value: MemberValue::String("Idle".to_string()),
+                }),
+            ])),
+            order_by: vec![OrderBy {
+                path: "stats.hp".to_string(),
+                direction: OrderDirection::Desc,
+            }],
+            limit: Some(2),
+            offset: 1,
+        };
+        assert_eq!(
+            names(&query.filter(&entities).unwrap()),
+            vec!["carol", "alice"]
+        );
+
+        let query = EntityQuery {
+            clause: Some(Clause::Member(MemberClause {
+                path: "name".to_string(),
+                operator: ComparisonOperator::In,
+                value: MemberValue::List(vec![
+                    MemberValue::String("alice".to_string()),
+                    MemberValue::String("carol".to_string()),
+                ]),
+            })),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&query.filter(&entities).unwrap()),
+            vec!["alice", "carol"]
+        );
+    }
+
+    #[test]
+    fn test_query_errors() {
+        let entities = players();
+
+        let query = EntityQuery {
+            clause: Some(Clause::Member(MemberClause {
+                path: "stats.mp".to_string(),
+                operator: ComparisonOperator::Eq,
+                value: MemberValue::Primitive(Primitive::U8(Some(1))),
+            })),
+            ..Default::default()
+        };
+        assert!(matches!(
+            query.filter(&entities),
+            Err(QueryError::MemberNotFound(_))
+        ));
+
+        let query = EntityQuery {
+            clause: Some(Clause::Member(MemberClause {
+                path: "stats.hp".to_string(),
+                operator: ComparisonOperator::Eq,
+                value: MemberValue::Primitive(Primitive::U32(Some(1))),
+            })),
+            ..Default::default()
+        };
+        assert!(matches!(
+            query.filter(&entities),
+            Err(QueryError::TypeMismatch(_))
+        ));
+
+        let query = EntityQuery {
+            clause: Some(Clause::Member(MemberClause {
+                path: "state".to_string(),
+                operator: ComparisonOperator::Lt,
+                value: MemberValue::String("Idle".to_string()),
+            })),
+            ..Default::default()
+        };
+        assert!(matches!(
+            query.to_sql(&model_metadata()),
+            Err(QueryError::UnsupportedOperator { .. })
+        ));
+    }
+
+    #[test]
+    fn test_to_sql() {
+        let query = EntityQuery {
+            clause: Some(Clause::And(vec![
+                Clause::Keys(KeysClause {
+                    keys: vec![KeyPattern::Exact(Felt::from(0x1a)), KeyPattern::Wildcard],
+                    pattern_matching: PatternMatching::VariableLen,
+                }),
+                hp_clause(ComparisonOperator::Neq, 5),
+                Clause::Member(MemberClause {
+                    path: "name".to_string(),
+                    operator: ComparisonOperator::Eq,
+                    value: MemberValue::String("o'neil".to_string()),
+                }),
+            ])),
+            order_by: vec![OrderBy {
+                path: "name".to_string(),
+                direction: OrderDirection::Asc,
+            }],
+            limit: None,
+            offset: 10,
+        };
+
+        assert_eq!(
+            query.to_sql(&model_metadata()).unwrap(),
+            "SELECT * FROM [ns-Player] WHERE ([keys] REGEXP '^0x1a/0x[0-9a-f]+/(0x[0-9a-f]+/)*$' \
+             AND [stats.hp] != 5 AND [name] = 'o''neil') ORDER BY [name] ASC LIMIT -1 OFFSET 10"
+        );
+
+        // the variant data are resolved on the schema, where no variant is selected.
+        let query = EntityQuery {
+            clause: Some(Clause::Member(MemberClause {
+                path: "state::Moving.0".to_string(),
+                operator: ComparisonOperator::Gt,
+                value: MemberValue::Primitive(Primitive::U32(Some(15))),
+            })),
+            order_by: vec![OrderBy {
+                path: "state::Moving.0".to_string(),
+                direction: OrderDirection::Desc,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            query.to_sql(&model_metadata()).unwrap(),
+            "SELECT * FROM [ns-Player] WHERE [state::Moving.0] > 15 ORDER BY [state::Moving.0] DESC"
+        );
+        assert_eq!(
+            names(&query.filter(&players()).unwrap()),
+            vec!["carol", "bob"]
+        );
+    }
+
+    #[test]
+    fn test_filter_and_sql_agree() {
+        let mut entities = players();
+        entities.push(player(4, "dave", 40, 0));
+
+        // an unset primitive is compared as its default value, as stored in SQL.
+        let mut unset = player(5, "eve", 0, 0);
+        if let Ty::Primitive(hp) = unset.get_mut("stats.hp").unwrap() {
+            *hp = Primitive::U8(None);
+        }
+        entities.push(unset);
+
+        let queries = vec![
+            EntityQuery {
+                clause: Some(hp_clause(ComparisonOperator::Lt, 15)),
+                ..Default::default()
+            },
+            EntityQuery {
+                clause: Some(hp_clause(ComparisonOperator::Eq, 0)),
+                ..Default::default()
+            },
+            EntityQuery {
+                clause: Some(Clause::And(vec![
+                    Clause::Keys(KeysClause {
+                        keys: vec![KeyPattern::Wildcard],
+                        pattern_matching: PatternMatching::FixedLen,
+                    }),
+                    hp_clause(ComparisonOperator::Gt, 10),
+                    Clause::Member(MemberClause {
+                        path: "state".to_string(),
+                        operator: ComparisonOperator::Neq,
+                        value: MemberValue::String("Idle".to_string()),
+                    }),
+                ])),
+                ..Default::default()
+            },
+            EntityQuery {
+                clause: Some(Clause::Or(vec![
+                    Clause::Keys(KeysClause {
+                        keys: vec![KeyPattern::Exact(Felt::from(4))],
+                        pattern_matching: PatternMatching::VariableLen,
+                    }),
+                    Clause::Member(MemberClause {
+                        path: "name".to_string(),
+                        operator: ComparisonOperator::In,
+                        value: MemberValue::List(vec![
+                            MemberValue::String("alice".to_string()),
+                            MemberValue::String("eve".to_string()),
+                        ]),
+                    }),
+                ])),
+                order_by: vec![OrderBy {
+                    path: "name".to_string(),
+                    direction: OrderDirection::Desc,
+                }],
+                ..Default::default()
+            },
+            EntityQuery {
+                clause: None,
+                order_by: vec![OrderBy {
+                    path: "stats.hp".to_string(),
+                    direction: OrderDirection::Asc,
+                }],
+                limit: Some(3),
+                offset: 1,
+            },
+            // the data of a variant which is not selected matches nothing, and
+            // is ordered first like `NULL`.
+            EntityQuery {
+                clause: Some(Clause::Member(MemberClause {
+                    path: "state::Moving.0".to_string(),
+                    operator: ComparisonOperator::Neq,
+                    value: MemberValue::Primitive(Primitive::U32(Some(20))),
+                })),
+                ..Default::default()
+            },
+            EntityQuery {
+                clause: None,
+                order_by: vec![
+                    OrderBy {
+                        path: "state::Moving.0".to_string(),
+                        direction: OrderDirection::Asc,
+                    },
+                    OrderBy {
+                        path: "name".to_string(),
+                        direction: OrderDirection::Desc,
+                    },
+                ],
+                ..Default::default()
+            },
+        ];
+
+        for query in queries {
+            let filtered = names(&query.filter(&entities).unwrap());
+            assert_eq!(filtered, sql_names(&query, &entities), "{query:?}");
+        }
+    }
+
+    #[test]
+    fn test_i128_members_are_not_ordered() {
+        let mut model = model_metadata();
+        if let Ty::Primitive(hp) = model.schema.get_mut("stats.hp").unwrap() {
+            *hp = Primitive::I128(Some(-1));
+        }
+        let entities = [model.schema.clone()];
+
+        let query = EntityQuery {
+            clause: Some(Clause::Member(MemberClause {
+                path: "stats.hp".to_string(),
+                operator: ComparisonOperator::Lt,
+                value: MemberValue::Primitive(Primitive::I128(Some(0))),
+            })),
+            ..Default::default()
+        };
+        assert!(matches!(
+            query.filter(&entities),
+            Err(QueryError::UnsupportedOperator { .. })
+        ));
+        assert!(matches!(
+            query.to_sql(&model),
+            Err(QueryError::UnsupportedOperator { .. })
+        ));
+
+        let query = EntityQuery {
+            order_by: vec![OrderBy {
+                path: "stats.hp".to_string(),
+                direction: OrderDirection::Asc,
+            }],
+            ..Default::default()
+        };
+        assert!(matches!(
+            query.filter(&entities),
+            Err(QueryError::UnorderedMember(_))
+        ));
+        assert!(matches!(
+            query.to_sql(&model),
+            Err(QueryError::UnorderedMember(_))
+        ));
+
+        // equality doesn't depend on the ordering.
+        let query = EntityQuery {
+            clause: Some(Clause::Member(MemberClause {
+                path: "stats.hp".to_string(),
+                operator: ComparisonOperator::Eq,
+                value: MemberValue::Primitive(Primitive::I128(Some(-1))),
+            })),
+            ..Default::default()
+        };
+        assert_eq!(query.filter(&entities).unwrap().len(), 1);
+        assert_eq!(
+            query.to_sql(&model).unwrap(),
+            format!(
+                "SELECT * FROM [ns-Player] WHERE [stats.hp] = '0x{:064x}'",
+                -1i128
+            )
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+
+use crate::event::WorldEvent;
+use crate::schema::ModelMetadata;
+use crate::world::{World, WorldError, WorldSnapshot};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("Unable to apply the event {index}: {source}")]
+    Event {
+        index: u64,
+        #[source]
+        source: WorldError,
+    },
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// An event of the ordered stream replayed into the World state.
+///
+/// The model registration and upgrade events of the World contract only contain the
+/// model class, so they are replayed with the model metadata read from this class.
+#[derive(Clone, Debug)]
+pub enum ReplayEvent {
+    /// A model registered by `owner`.
+    ModelRegistered {
+        model: ModelMetadata,
+        owner: Felt,
+    },
+    ModelUpgraded(ModelMetadata),
+    World(WorldEvent),
+}
+
+/// A checkpoint of a replay, to resume it from the next event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySnapshot {
+    /// The number of events replayed since the World creation.
+    pub events_applied: u64,
+    pub world: WorldSnapshot,
+}
+
+/// Rebuilds the entities state of a World by replaying its ordered events,
+/// with JSON snapshots to resume a replay without starting over from the World creation.
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    world: World,
+    events_applied: u64,
+}
+
+impl Replayer {
+    /// Creates a replayer starting from the given World state, usually a new World.
+    pub fn new(world: World) -> Self {
+        Self {
+            world,
+            events_applied: 0,
+        }
+    }
+
+    /// Restores a replayer from a snapshot.
+    pub fn from_snapshot(snapshot: ReplaySnapshot) -> Self {
+        Self {
+            world: World::from_snapshot(snapshot.world),
+            events_applied: snapshot.events_applied,
+        }
+    }
+
+    /// Restores a replayer from a JSON snapshot, as returned by [Replayer::to_json].
+    pub fn from_json(json: &str) -> Result<Self, ReplayError> {
+        Ok(Self::from_snapshot(serde_json::from_str(json)?))
+    }
+
+    /// Returns the rebuilt World state.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Returns the number of events replayed since the World creation, which is also
+    /// the index of the next event to replay.
+    pub fn events_applied(&self) -> u64 {
+        self.events_applied
+    }
+
+    /// Applies the next event of the stream. On error, the event is not counted as
+    /// applied, so it can be retried.
+    pub fn apply(&mut self, event: &ReplayEvent) -> Result<(), ReplayError> {
+        let result = match event {
+            ReplayEvent::ModelRegistered { model, owner } => {
+                self.world.register_model(model.clone(), *owner).map(|_| ())
+            }
+            ReplayEvent::ModelUpgraded(model) => {
+                self.world.upgrade_model(model.clone()).map(|_| ())
+            }
+            ReplayEvent::World(event) => self.world.apply_event(event),
+        };
+
+        result.map_err(|source| ReplayError::Event {
+            index: self.events_applied,
+            source,
+        })?;
+        self.events_applied += 1;
+
+        Ok(())
+    }
+
+    /// Applies the next events of the stream, in order.
+    pub fn replay<'a>(
+        &mut self,
+        events: impl IntoIterator<Item = &'a ReplayEvent>,
+    ) -> Result<(), ReplayError> {
+        events.into_iter().try_for_each(|event| self.apply(event))
+    }
+
+    /// Returns a snapshot of the replay.
+    pub fn snapshot(&self) -> ReplaySnapshot {
+        ReplaySnapshot {
+            events_applied: self.events_applied,
+            world: self.world.snapshot(),
+        }
+    }
+
+    /// Returns a JSON snapshot of the replay.
+    pub fn to_json(&self) -> Result<String, ReplayError> {
+        Ok(serde_json::to_string(&self.snapshot())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{NamespaceRegistered, WorldStoreDelRecord, WorldStoreSetRecord};
+    use crate::naming::{compute_bytearray_hash, compute_selector_from_names};
+    use crate::primitive::Primitive;
+    use crate::schema::{Enum, EnumOption, Member, Struct, Ty};
+    use crate::storage::entity_id_from_keys;
+
+    fn member(name: &str, ty: Ty, key: bool) -> Member {
+        Member {
+            name: name.to_string(),
+            ty,
+            key,
+        }
+    }
+
+    fn state_enum(options: &[&str]) -> Ty {
+        Ty::Enum(Enum {
+            name: "State".to_string(),
+            option: None,
+            options: options
+                .iter()
+                .map(|name| EnumOption {
+                    name: name.to_string(),
+                    ty: Ty::Tuple(vec![]),
+                })
+                .collect(),
+        })
+    }
+
+    fn player_model(children: Vec<Member>) -> ModelMetadata {
+        ModelMetadata {
+            schema: Ty::Struct(Struct {
+                name: "Player".to_string(),
+                children,
+            }),
+            namespace: "ns".to_string(),
+            name: "Player".to_string(),
+            packed_size: 0,
+            unpacked_size: 0,
+            class_hash: Felt::ZERO,
+            contract_address: Felt::ZERO,
+            layout: vec![],
+        }
+    }
+
+    fn set_record(id: u32, values: &[u64]) -> ReplayEvent {
+        let keys = vec![Felt::from(id)];
+
+        ReplayEvent::World(WorldEvent::StoreSetRecord(WorldStoreSetRecord {
+            selector: compute_selector_from_names("ns", "Player"),
+            entity_id: entity_id_from_keys(&keys),
+            keys,
+            values: values.iter().map(|v| Felt::from(*v)).collect(),
+        }))
+    }
+
+    fn events() -> Vec<ReplayEvent> {
+        let v1 = player_model(vec![
+            member("id", Ty::Primitive(Primitive::U32(None)), true),
+            member("score", Ty::Primitive(Primitive::U32(None)), false),
+            member("state", state_enum(&["Idle", "Moving"]), false),
+        ]);
+        // score is widened, a member and a variant are added.
+        let v2 = player_model(vec![
+            member("id", Ty::Primitive(Primitive::U32(None)), true),
+            member("score", Ty::Primitive(Primitive::U64(None)), false),
+            member("state", state_enum(&["Idle", "Moving", "Dead"]), false),
+            member("level", Ty::Primitive(Primitive::U8(None)), false),
+        ]);
+
+        vec![
+            ReplayEvent::World(WorldEvent::NamespaceRegistered(NamespaceRegistered {
+                namespace: "ns".to_string(),
+                hash: compute_bytearray_hash("ns"),
+            })),
+            ReplayEvent::ModelRegistered {
+                model: v1,
+                owner: Felt::ONE,
+            },
+            set_record(1, &[10, 1]),
+            set_record(2, &[20, 0]),
+            ReplayEvent::ModelUpgraded(v2),
+            set_record(3, &[30, 2, 5]),
+            ReplayEvent::World(WorldEvent::StoreDelRecord(WorldStoreDelRecord {
+                selector: compute_selector_from_names("ns", "Player"),
+                entity_id: entity_id_from_keys(&[Felt::TWO]),
+            })),
+        ]
+    }
+
+    #[test]
+    fn test_replay_with_upgrade() {
+        let mut replayer = Replayer::new(World::new(Felt::ZERO, Felt::ONE));
+        replayer.replay(&events()).unwrap();
+
+        let world = replayer.world();
+        let model = compute_selector_from_names("ns", "Player");
+        assert_eq!(replayer.events_applied(), 7);
+        assert_eq!(world.entities(&model).count(), 2);
+        assert!(world.is_owner(&model, &Felt::ONE));
+
+        let player = world
+            .entity_from_keys(&model, &[Felt::ONE])
+            .unwrap()
+            .as_struct()
+            .unwrap();
+        assert_eq!(
+            player.get("score"),
+            Some(&Ty::Primitive(Primitive::U64(Some(10))))
+        );
+        assert_eq!(
+            player.get("level"),
+            Some(&Ty::Primitive(Primitive::U8(Some(0))))
+        );
+        assert_eq!(
+            player
+                .get("state")
+                .unwrap()
+                .as_enum()
+                .unwrap()
+                .to_sql_value(),
+            "Moving"
+        );
+
+        let player = world
+            .entity_from_keys(&model, &[Felt::THREE])
+            .unwrap()
+            .as_struct()
+            .unwrap();
+        assert_eq!(
+            player
+                .get("state")
+                .unwrap()
+                .as_enum()
+                .unwrap()
+                .to_sql_value(),
+            "Dead"
+        );
+    }
+
+    #[test]
+    fn test_resume_from_snapshot() {
+        let events = events();
+
+        let mut full = Replayer::new(World::new(Felt::ZERO, Felt::ONE));
+        full.replay(&events).unwrap();
+
+        let mut partial = Replayer::new(World::new(Felt::ZERO, Felt::ONE));
+        partial.replay(&events[..3]).unwrap();
+
+        let mut resumed = Replayer::from_json(&partial.to_json().unwrap()).unwrap();
+        assert_eq!(resumed.events_applied(), 3);
+        resumed
+            .replay(&events[resumed.events_applied() as usize..])
+            .unwrap();
+
+        assert_eq!(resumed.to_json().unwrap(), full.to_json().unwrap());
+    }
+
+    #[test]
+    fn test_incompatible_upgrade() {
+        let mut replayer = Replayer::new(World::new(Felt::ZERO, Felt::ONE));
+        replayer.replay(&events()[..4]).unwrap();
+
+        let upgrade = ReplayEvent::ModelUpgraded(player_model(vec![
+            member("id", Ty::Primitive(Primitive::U32(None)), true),
+            member("score", Ty::Primitive(Primitive::U32(None)), false),
+            member("state", state_enum(&["Idle"]), false),
+        ]));
+
+        assert!(matches!(
+            replayer.apply(&upgrade),
+            Err(ReplayError::Event {
+                index: 4,
+                source: WorldError::IncompatibleUpgrade { .. }
+            })
+        ));
+        assert_eq!(replayer.events_applied(), 4);
+    }
+}
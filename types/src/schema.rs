@@ -40,6 +40,14 @@ pub struct ModelMetadata {
     pub layout: Vec<Felt>,
 }
 
+impl ModelMetadata {
+    /// Returns true if the model is a singleton, i.e a model without keys
+    /// holding a global state under a fixed entity id.
+    pub fn is_singleton(&self) -> bool {
+        self.schema.as_struct().is_some_and(|s| s.is_singleton())
+    }
+}
+
 /// Represents all possible types in Cairo
 #[derive(AsRefStr, Clone, Debug, Serialize, Deserialize, PartialEq, Hash, Eq)]
 #[serde(tag = "type", content = "content")]
@@ -92,6 +100,15 @@ impl Ty {
         }
     }
 
+    /// If the `Ty` is a struct, returns the associated mutable [`Struct`]. Returns `None`
+    /// otherwise.
+    pub fn as_struct_mut(&mut self) -> Option<&mut Struct> {
+        match self {
+            Ty::Struct(s) => Some(s),
+            _ => None,
+        }
+    }
+
     /// If the `Ty` is an enum, returns the associated [`Enum`]. Returns `None` otherwise.
     pub fn as_enum(&self) -> Option<&Enum> {
         match self {
@@ -144,9 +161,11 @@ impl Ty {
                         .unwrap_or(Err(PrimitiveError::MissingFieldElement))?;
                     felts.extend(option);
 
-                    for EnumOption { ty, .. } in &e.options {
-                        serialize_inner(ty, felts)?;
-                    }
+                    // only the data of the selected option is serialized, as done by `Serde`.
+                    let option = e
+                        .option()
+                        .map_err(|_| PrimitiveError::MissingFieldElement)?;
+                    serialize_inner(&option.ty, felts)?;
                 }
                 Ty::Tuple(tys) => {
                     for ty in tys {
@@ -622,6 +641,12 @@ impl Struct {
     pub fn keys(&self) -> Vec<Member> {
         self.children.iter().filter(|m| m.key).cloned().collect()
     }
+
+    /// Returns true if the struct has no key members. For a model, it means the model
+    /// is a singleton, stored under [crate::storage::singleton_entity_id].
+    pub fn is_singleton(&self) -> bool {
+        !self.children.iter().any(|m| m.key)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -1004,4 +1029,56 @@ mod tests {
         let same_struct = struct2.diff(&struct2);
         assert!(same_struct.is_none());
     }
+
+    #[test]
+    fn test_struct_is_singleton() {
+        let mut s = Struct {
+            name: "Config".to_string(),
+            children: vec![Member {
+                name: "fee".to_string(),
+                ty: Ty::Primitive(Primitive::U32(None)),
+                key: false,
+            }],
+        };
+
+        assert!(s.is_singleton());
+        assert!(s.keys().is_empty());
+
+        s.children[0].key = true;
+        assert!(!s.is_singleton());
+    }
+
+    #[test]
+    fn test_serialize_enum_selected_option() {
+        let mut ty = Ty::Enum(Enum {
+            name: "Direction".to_string(),
+            option: None,
+            options: vec![
+                EnumOption {
+                    name: "None".to_string(),
+                    ty: Ty::Tuple(vec![]),
+                },
+                EnumOption {
+                    name: "Left".to_string(),
+                    ty: Ty::Primitive(Primitive::U8(Some(3))),
+                },
+                EnumOption {
+                    name: "Right".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(Some(5))),
+                },
+            ],
+        });
+
+        assert!(ty.serialize().is_err());
+
+        if let Ty::Enum(e) = &mut ty {
+            e.set_option("Right").unwrap();
+        }
+        assert_eq!(ty.serialize().unwrap(), vec![Felt::TWO, Felt::from(5)]);
+
+        if let Ty::Enum(e) = &mut ty {
+            e.set_option("None").unwrap();
+        }
+        assert_eq!(ty.serialize().unwrap(), vec![Felt::ZERO]);
+    }
 }
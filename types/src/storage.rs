@@ -1,7 +1,492 @@
+use num_traits::ToPrimitive;
 use starknet::core::types::Felt;
+use starknet::core::utils::cairo_short_string_to_felt;
+use starknet_crypto::poseidon_hash_many;
+
+use crate::layout::Layout;
+use crate::packing::PACKING_MAX_BITS;
+use crate::primitive::PrimitiveError;
+use crate::schema::Ty;
+
+/// The upper bound of storage addresses (`2**251 - 256`), as used by
+/// `storage_base_address_from_felt252`.
+const ADDR_BOUND: Felt =
+    Felt::from_hex_unchecked("0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff00");
+
+/// The number of storage addresses of a storage segment, before moving to the next chunk.
+const CHUNK_SIZE: usize = 256;
+
+/// The minimum number of values of a serialized `ByteArray`.
+const MIN_BYTE_ARRAY_SIZE: usize = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Missing values at offset {0} to compute the storage addresses.")]
+    MissingValues(usize),
+    #[error("Invalid array length ({0:#x}).")]
+    InvalidArrayLength(Felt),
+    #[error("Unable to find the layout of the enum variant {0:#x}.")]
+    UnknownVariant(Felt),
+}
 
 #[derive(Clone, Debug)]
 pub struct Query {
     pub address_domain: u32,
     pub keys: Vec<Felt>,
 }
+
+impl Query {
+    /// Returns the entity id of the queried keys.
+    pub fn entity_id(&self) -> Felt {
+        entity_id_from_keys(&self.keys)
+    }
+}
+
+/// Computes the entity id of a model instance from its serialized keys,
+/// as `dojo::utils::entity_id_from_serialized_keys` does.
+pub fn entity_id_from_keys(keys: &[Felt]) -> Felt {
+    poseidon_hash_many(keys)
+}
+
+/// Returns the fixed entity id of singleton models (models without keys).
+pub fn singleton_entity_id() -> Felt {
+    entity_id_from_keys(&[])
+}
+
+/// Computes the entity id of a model instance from its keys, serialized as
+/// the world does (i.e a struct or a tuple of the model keys).
+pub fn entity_id_from_key_ty(keys: &Ty) -> Result<Felt, PrimitiveError> {
+    Ok(entity_id_from_keys(&keys.serialize()?))
+}
+
+/// Combines a parent key with a child key (a member selector, an item index or
+/// a variant index), as `dojo::utils::combine_key` does.
+pub fn combine_key(parent_key: Felt, child_key: Felt) -> Felt {
+    poseidon_hash_many(&[parent_key, child_key])
+}
+
+/// Computes the base storage address of a record of a model, as
+/// `dojo::storage::database` does from the model selector and the record key.
+pub fn storage_base_address(model_selector: Felt, key: Felt) -> Felt {
+    let dojo_storage = cairo_short_string_to_felt("dojo_storage").unwrap();
+    felt_to_base_address(poseidon_hash_many(&[dojo_storage, model_selector, key]))
+}
+
+/// Returns the `size` consecutive storage addresses of a record of a model. After 256
+/// addresses, the next ones are stored in another storage segment, as
+/// `dojo::storage::storage` does.
+pub fn record_storage_addresses(model_selector: Felt, key: Felt, size: usize) -> Vec<Felt> {
+    let base = storage_base_address(model_selector, key);
+    let chunk_marker = cairo_short_string_to_felt("DojoStorageChunk").unwrap();
+
+    (0..size)
+        .map(|i| {
+            let chunk = i / CHUNK_SIZE;
+            let chunk_base = if chunk == 0 {
+                base
+            } else {
+                felt_to_base_address(poseidon_hash_many(&[base, Felt::from(chunk), chunk_marker]))
+            };
+
+            chunk_base + Felt::from(i % CHUNK_SIZE)
+        })
+        .collect()
+}
+
+/// Returns the number of felts used to store a fixed layout, once packed, as
+/// `dojo::storage::packing::calculate_packed_size` does.
+pub fn packed_storage_size(layout: &[u8]) -> usize {
+    let mut size = 1;
+    let mut partial = 0;
+
+    for item in layout {
+        partial += *item as usize;
+
+        if partial > PACKING_MAX_BITS {
+            size += 1;
+            partial = *item as usize;
+        }
+    }
+
+    size
+}
+
+/// A record written in the world storage for a part of a model instance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageRecord {
+    /// The member selectors, item indexes and variant indexes leading to the record,
+    /// from the model root.
+    pub path: Vec<Felt>,
+    /// The record key, combining the entity id with the path.
+    pub key: Felt,
+    /// The storage addresses of the record.
+    pub addresses: Vec<Felt>,
+}
+
+/// Returns the storage records of a model instance, as written by
+/// `dojo::storage::layout::write_layout`, in storage order.
+///
+/// The storage used by arrays, byte arrays and enums depends on the model values.
+/// When `values` (the serialized model values) is not provided, only the first address
+/// of their record (holding the length or the variant) is returned, and their content
+/// is skipped.
+pub fn model_storage_records(
+    model_selector: Felt,
+    entity_id: Felt,
+    layout: &Layout,
+    values: Option<&[Felt]>,
+) -> Result<Vec<StorageRecord>, StorageError> {
+    let mut records = vec![];
+    let mut offset = 0;
+
+    collect_storage_records(
+        model_selector,
+        entity_id,
+        &mut vec![],
+        layout,
+        values,
+        &mut offset,
+        &mut records,
+    )?;
+
+    Ok(records)
+}
+
+fn collect_storage_records(
+    model_selector: Felt,
+    key: Felt,
+    path: &mut Vec<Felt>,
+    layout: &Layout,
+    values: Option<&[Felt]>,
+    offset: &mut usize,
+    records: &mut Vec<StorageRecord>,
+) -> Result<(), StorageError> {
+    let mut push_record = |size: usize| {
+        records.push(StorageRecord {
+            path: path.clone(),
+            key,
+            addresses: record_storage_addresses(model_selector, key, size),
+        })
+    };
+
+    match layout {
+        Layout::Fixed(widths) => {
+            if let Some(values) = values {
+                if !widths.is_empty() {
+                    value_at(values, *offset + widths.len() - 1)?;
+                }
+            }
+
+            push_record(packed_storage_size(widths));
+            *offset += widths.len();
+        }
+        Layout::Struct(fields) => {
+            for field in fields {
+                path.push(field.selector);
+                collect_storage_records(
+                    model_selector,
+                    combine_key(key, field.selector),
+                    path,
+                    &field.layout,
+                    values,
+                    offset,
+                    records,
+                )?;
+                path.pop();
+            }
+        }
+        Layout::Tuple(items) => {
+            for (i, item) in items.iter().enumerate() {
+                let index = Felt::from(i);
+
+                path.push(index);
+                collect_storage_records(
+                    model_selector,
+                    combine_key(key, index),
+                    path,
+                    item,
+                    values,
+                    offset,
+                    records,
+                )?;
+                path.pop();
+            }
+        }
+        Layout::Array(item) => {
+            push_record(1);
+
+            let Some(values) = values else {
+                return Ok(());
+            };
+
+            let len = value_at(values, *offset)?;
+            let len = len.to_u32().ok_or(StorageError::InvalidArrayLength(len))?;
+            *offset += 1;
+
+            for i in 0..len {
+                let index = Felt::from(i);
+
+                path.push(index);
+                collect_storage_records(
+                    model_selector,
+                    combine_key(key, index),
+                    path,
+                    item,
+                    Some(values),
+                    offset,
+                    records,
+                )?;
+                path.pop();
+            }
+        }
+        Layout::ByteArray => {
+            let Some(values) = values else {
+                push_record(1);
+                return Ok(());
+            };
+
+            let data_len = value_at(values, *offset)?;
+            let size = data_len
+                .to_u32()
+                .and_then(|len| (len as usize).checked_add(MIN_BYTE_ARRAY_SIZE))
+                .ok_or(StorageError::InvalidArrayLength(data_len))?;
+
+            value_at(values, *offset + size - 1)?;
+            push_record(size);
+            *offset += size;
+        }
+        Layout::Enum(variants) => {
+            push_record(1);
+
+            let Some(values) = values else {
+                return Ok(());
+            };
+
+            let variant = value_at(values, *offset)?;
+            *offset += 1;
+
+            let variant_layout = variants
+                .iter()
+                .find(|v| v.selector == variant)
+                .ok_or(StorageError::UnknownVariant(variant))?;
+
+            path.push(variant);
+            collect_storage_records(
+                model_selector,
+                combine_key(key, variant),
+                path,
+                &variant_layout.layout,
+                Some(values),
+                offset,
+                records,
+            )?;
+            path.pop();
+        }
+    }
+
+    Ok(())
+}
+
+fn value_at(values: &[Felt], offset: usize) -> Result<Felt, StorageError> {
+    values
+        .get(offset)
+        .copied()
+        .ok_or(StorageError::MissingValues(offset))
+}
+
+/// Reduces a felt to a storage base address, modulo `2**251 - 256`.
+fn felt_to_base_address(value: Felt) -> Felt {
+    // felts are lower than twice the address bound, and big-endian bytes
+    // are compared as integers.
+    if value.to_bytes_be() >= ADDR_BOUND.to_bytes_be() {
+        value - ADDR_BOUND
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::FieldLayout;
+    use crate::primitive::Primitive;
+    use crate::schema::{Enum, EnumOption, Member, Struct};
+
+    #[test]
+    fn test_singleton_entity_id() {
+        let query = Query {
+            address_domain: 0,
+            keys: vec![],
+        };
+
+        assert_eq!(query.entity_id(), singleton_entity_id());
+        assert_ne!(singleton_entity_id(), entity_id_from_keys(&[Felt::ZERO]));
+    }
+
+    #[test]
+    fn test_entity_id_from_key_ty() {
+        let keys = Ty::Tuple(vec![
+            Ty::Primitive(Primitive::U32(Some(7))),
+            Ty::Primitive(Primitive::U256(Some(crypto_bigint::U256::from_u8(1)))),
+        ]);
+
+        assert_eq!(
+            entity_id_from_key_ty(&keys).unwrap(),
+            entity_id_from_keys(&[Felt::from(7), Felt::ONE, Felt::ZERO])
+        );
+    }
+
+    #[test]
+    fn test_entity_id_from_enum_key_ty() {
+        let keys = Ty::Tuple(vec![Ty::Enum(Enum {
+            name: "Direction".to_string(),
+            option: Some(1),
+            options: vec![
+                EnumOption {
+                    name: "Left".to_string(),
+                    ty: Ty::Primitive(Primitive::U8(Some(3))),
+                },
+                EnumOption {
+                    name: "Right".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(Some(5))),
+                },
+            ],
+        })]);
+
+        // only the data of the selected variant is part of the keys.
+        assert_eq!(
+            entity_id_from_key_ty(&keys).unwrap(),
+            entity_id_from_keys(&[Felt::ONE, Felt::from(5)])
+        );
+    }
+
+    #[test]
+    fn test_packed_storage_size() {
+        assert_eq!(packed_storage_size(&[]), 1);
+        assert_eq!(packed_storage_size(&[128, 123]), 1);
+        assert_eq!(packed_storage_size(&[128, 124]), 2);
+        assert_eq!(packed_storage_size(&[251, 251, 8]), 3);
+    }
+
+    #[test]
+    fn test_record_storage_addresses() {
+        let model = Felt::from(0x1234);
+        let key = Felt::from(0x5678);
+        let base = storage_base_address(model, key);
+        let addresses = record_storage_addresses(model, key, 258);
+
+        assert_eq!(addresses.len(), 258);
+        assert_eq!(addresses[0], base);
+        assert_eq!(addresses[255], base + Felt::from(255));
+
+        let chunk_base = felt_to_base_address(poseidon_hash_many(&[
+            base,
+            Felt::ONE,
+            cairo_short_string_to_felt("DojoStorageChunk").unwrap(),
+        ]));
+        assert_eq!(addresses[256], chunk_base);
+        assert_eq!(addresses[257], chunk_base + Felt::ONE);
+
+        assert!(addresses
+            .iter()
+            .all(|a| a.to_bytes_be() < ADDR_BOUND.to_bytes_be()));
+        assert_eq!(felt_to_base_address(ADDR_BOUND + Felt::TWO), Felt::TWO);
+    }
+
+    #[test]
+    fn test_model_storage_records() {
+        let model = Felt::from(0x1234);
+        let entity_id = entity_id_from_key_ty(&Ty::Struct(Struct {
+            name: "Keys".to_string(),
+            children: vec![Member {
+                name: "id".to_string(),
+                ty: Ty::Primitive(Primitive::U32(Some(1))),
+                key: true,
+            }],
+        }))
+        .unwrap();
+        assert_eq!(entity_id, entity_id_from_keys(&[Felt::ONE]));
+
+        // struct { a: (u8, u128), b: Array<felt252>, c: Option<u16> }
+        let (a, b, c) = (Felt::from(0xa), Felt::from(0xb), Felt::from(0xc));
+        let layout = Layout::Struct(vec![
+            FieldLayout {
+                selector: a,
+                layout: Layout::Tuple(vec![Layout::Fixed(vec![8]), Layout::Fixed(vec![128])]),
+            },
+            FieldLayout {
+                selector: b,
+                layout: Layout::Array(Box::new(Layout::Fixed(vec![251]))),
+            },
+            FieldLayout {
+                selector: c,
+                layout: Layout::Enum(vec![
+                    FieldLayout {
+                        selector: Felt::ZERO,
+                        layout: Layout::Fixed(vec![16]),
+                    },
+                    FieldLayout {
+                        selector: Felt::ONE,
+                        layout: Layout::Fixed(vec![]),
+                    },
+                ]),
+            },
+        ]);
+
+        let records = model_storage_records(model, entity_id, &layout, None).unwrap();
+        let paths = records.iter().map(|r| r.path.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![vec![a, Felt::ZERO], vec![a, Felt::ONE], vec![b], vec![c]]
+        );
+
+        let b_key = combine_key(entity_id, b);
+        assert_eq!(records[2].key, b_key);
+        assert_eq!(
+            records[2].addresses,
+            vec![storage_base_address(model, b_key)]
+        );
+
+        // a = (1, 2), b = [3, 4], c = Option::Some(5)
+        let values = [1, 2, 2, 3, 4, 0, 5].map(Felt::from);
+        let records = model_storage_records(model, entity_id, &layout, Some(&values)).unwrap();
+        let paths = records.iter().map(|r| r.path.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                vec![a, Felt::ZERO],
+                vec![a, Felt::ONE],
+                vec![b],
+                vec![b, Felt::ZERO],
+                vec![b, Felt::ONE],
+                vec![c],
+                vec![c, Felt::ZERO],
+            ]
+        );
+        assert_eq!(records[4].key, combine_key(b_key, Felt::ONE));
+
+        assert!(matches!(
+            model_storage_records(model, entity_id, &layout, Some(&values[..4])),
+            Err(StorageError::MissingValues(4))
+        ));
+    }
+
+    #[test]
+    fn test_byte_array_storage_records() {
+        let model = Felt::from(0x1234);
+        let values = [1, 0x61, 0x62, 1].map(Felt::from);
+
+        let records =
+            model_storage_records(model, Felt::ONE, &Layout::ByteArray, Some(&values)).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].addresses,
+            record_storage_addresses(model, Felt::ONE, 4)
+        );
+
+        assert!(
+            model_storage_records(model, Felt::ONE, &Layout::ByteArray, Some(&values[..3]))
+                .is_err()
+        );
+    }
+}
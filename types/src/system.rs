@@ -1,4 +1,26 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use starknet::core::types::Felt;
+
+use crate::primitive::PrimitiveError;
+use crate::schema::Ty;
+
+#[derive(Debug, thiserror::Error)]
+pub enum InitCalldataError {
+    #[error("The init schema must be a struct of the dojo_init parameters.")]
+    InvalidSchema,
+    #[error("The init arguments must be a JSON object.")]
+    InvalidArguments,
+    #[error("Missing init argument '{0}'.")]
+    MissingArgument(String),
+    #[error("Unknown init argument '{0}'.")]
+    UnknownArgument(String),
+    #[error("Invalid value for init argument '{name}': {source}")]
+    InvalidArgument {
+        name: String,
+        source: PrimitiveError,
+    },
+}
 
 /// Represents a system's model dependency.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -8,3 +30,185 @@ pub struct Dependency {
     pub read: bool,
     pub write: bool,
 }
+
+/// Represents the information exported by the `dojo::contract` attribute
+/// about a Dojo contract, to be used by deployment tools.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContractManifest {
+    /// Name of the contract.
+    pub name: String,
+    /// Namespace declared in the contract attribute, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Model dependencies declared in the contract attribute.
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    /// Model dependencies detected in the contract code, with explicitly typed models only.
+    #[serde(default)]
+    pub detected_dependencies: Vec<Dependency>,
+    /// Parameters of the `dojo_init` function, as a struct with one member per parameter.
+    /// Not set if a parameter type is not supported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub init_schema: Option<Ty>,
+}
+
+/// Encode the init arguments of a contract, provided as a JSON object
+/// (i.e `{ "max_players": 10, "name": "arena" }`) into the calldata expected
+/// by its `dojo_init` function, following the parameters order of the init schema.
+pub fn encode_init_calldata(schema: &Ty, args: &JsonValue) -> Result<Vec<Felt>, InitCalldataError> {
+    let Ty::Struct(schema) = schema else {
+        return Err(InitCalldataError::InvalidSchema);
+    };
+
+    let JsonValue::Object(args) = args else {
+        return Err(InitCalldataError::InvalidArguments);
+    };
+
+    if let Some(unknown) = args
+        .keys()
+        .find(|name| !schema.children.iter().any(|m| &m.name == *name))
+    {
+        return Err(InitCalldataError::UnknownArgument(unknown.clone()));
+    }
+
+    let mut calldata = vec![];
+
+    for member in &schema.children {
+        let value = args
+            .get(&member.name)
+            .ok_or_else(|| InitCalldataError::MissingArgument(member.name.clone()))?;
+
+        let mut ty = member.ty.clone();
+        ty.from_json_value(value.clone())
+            .and_then(|_| ty.serialize())
+            .map(|felts| calldata.extend(felts))
+            .map_err(|source| InitCalldataError::InvalidArgument {
+                name: member.name.clone(),
+                source,
+            })?;
+    }
+
+    Ok(calldata)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::primitive::Primitive;
+    use crate::schema::{Enum, EnumOption, Member, Struct};
+
+    fn init_schema() -> Ty {
+        Ty::Struct(Struct {
+            name: "dojo_init".to_string(),
+            children: vec![
+                Member {
+                    name: "max_players".to_string(),
+                    ty: Ty::Primitive(Primitive::U8(None)),
+                    key: false,
+                },
+                Member {
+                    name: "prizes".to_string(),
+                    ty: Ty::Array(vec![Ty::Primitive(Primitive::U128(None))]),
+                    key: false,
+                },
+                Member {
+                    name: "owner".to_string(),
+                    ty: Ty::Primitive(Primitive::ContractAddress(None)),
+                    key: false,
+                },
+            ],
+        })
+    }
+
+    #[test]
+    fn test_encode_init_calldata() {
+        let calldata = encode_init_calldata(
+            &init_schema(),
+            &json!({ "owner": "0x123", "max_players": 4, "prizes": ["100", "50"] }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            calldata,
+            vec![
+                Felt::from(4),
+                Felt::from(2),
+                Felt::from(100),
+                Felt::from(50),
+                Felt::from(0x123)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_init_calldata_with_enum() {
+        let schema = Ty::Struct(Struct {
+            name: "dojo_init".to_string(),
+            children: vec![Member {
+                name: "mode".to_string(),
+                ty: Ty::Enum(Enum {
+                    name: "Mode".to_string(),
+                    option: None,
+                    options: vec![
+                        EnumOption {
+                            name: "Solo".to_string(),
+                            ty: Ty::Tuple(vec![]),
+                        },
+                        EnumOption {
+                            name: "Teams".to_string(),
+                            ty: Ty::Primitive(Primitive::U8(None)),
+                        },
+                    ],
+                }),
+                key: false,
+            }],
+        });
+
+        assert_eq!(
+            encode_init_calldata(&schema, &json!({ "mode": { "Teams": 2 } })).unwrap(),
+            vec![Felt::ONE, Felt::TWO]
+        );
+        assert_eq!(
+            encode_init_calldata(&schema, &json!({ "mode": { "Solo": [] } })).unwrap(),
+            vec![Felt::ZERO]
+        );
+    }
+
+    #[test]
+    fn test_encode_init_calldata_errors() {
+        let schema = init_schema();
+
+        assert!(matches!(
+            encode_init_calldata(&schema, &json!({ "max_players": 4, "prizes": [] })),
+            Err(InitCalldataError::MissingArgument(name)) if name == "owner"
+        ));
+
+        assert!(matches!(
+            encode_init_calldata(
+                &schema,
+                &json!({ "max_players": 4, "prizes": [], "owner": "0x1", "other": 1 })
+            ),
+            Err(InitCalldataError::UnknownArgument(name)) if name == "other"
+        ));
+
+        assert!(matches!(
+            encode_init_calldata(
+                &schema,
+                &json!({ "max_players": "4", "prizes": [], "owner": "0x1" })
+            ),
+            Err(InitCalldataError::InvalidArgument { name, .. }) if name == "max_players"
+        ));
+
+        assert!(matches!(
+            encode_init_calldata(&schema, &json!([4])),
+            Err(InitCalldataError::InvalidArguments)
+        ));
+
+        assert!(matches!(
+            encode_init_calldata(&Ty::ByteArray("".to_string()), &json!({})),
+            Err(InitCalldataError::InvalidSchema)
+        ));
+    }
+}
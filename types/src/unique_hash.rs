@@ -0,0 +1,365 @@
+//! Unique hash of models and events, hardcoded in their generated contracts so each
+//! definition gets its own class hash.
+//!
+//! The hash is computed by the Dojo macros from the Cairo source, and can be computed
+//! again from the [Ty] of a model or an event to verify that an on-chain class matches
+//! its source.
+
+use starknet::core::types::Felt;
+use starknet_crypto::poseidon_hash_many;
+
+use crate::layout::Layout;
+use crate::naming;
+use crate::packing::narrowed_member_bits;
+use crate::schema::{Struct, Ty};
+
+/// Version of the unique hash scheme, to be bumped when the hashed data change.
+pub const UNIQUE_HASH_VERSION: u8 = 1;
+
+/// Delimiters of a Cairo type path.
+const TYPE_DELIMITERS: [char; 7] = ['<', '>', '(', ')', ',', '[', ']'];
+
+/// Generic types whose arguments are part of the schema, and so are kept in type paths.
+const GENERIC_TYPES: [&str; 2] = ["Array", "Option"];
+
+/// A stored member of a model or an event, as hashed by [compute_unique_hash].
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniqueHashMember {
+    pub name: String,
+    /// Type path of the member, normalized with [normalize_type_path].
+    pub type_path: String,
+    pub key: bool,
+    /// Bit width of a packed member narrowed with `#[bits(N)]`.
+    pub bits: Option<u8>,
+}
+
+/// Computes the unique hash of a model or an event from its name, its packing and
+/// its stored members (skipped members excluded), in declaration order.
+pub fn compute_unique_hash(
+    element_name: &str,
+    is_packed: bool,
+    members: &[UniqueHashMember],
+) -> Felt {
+    let mut hashes = vec![
+        Felt::from(UNIQUE_HASH_VERSION),
+        if is_packed { Felt::ONE } else { Felt::ZERO },
+        naming::compute_bytearray_hash(element_name),
+        Felt::from(members.len()),
+    ];
+
+    hashes.extend(members.iter().map(|m| {
+        poseidon_hash_many(&[
+            naming::compute_bytearray_hash(&m.name),
+            naming::compute_bytearray_hash(&m.type_path),
+            if m.key { Felt::ONE } else { Felt::ZERO },
+            Felt::from(m.bits.unwrap_or_default()),
+        ])
+    }));
+
+    poseidon_hash_many(&hashes)
+}
+
+/// Computes the unique hash of a model or an event from its schema, which must be a
+/// struct, and its layout, which carries the packing and the bit width of the members
+/// narrowed with `#[bits(N)]`. Returns `None` if the schema is not a struct or does
+/// not match the layout.
+pub fn compute_unique_hash_from_ty(ty: &Ty, layout: &Layout) -> Option<Felt> {
+    let Ty::Struct(Struct { name, children }) = ty else {
+        return None;
+    };
+
+    let bits = narrowed_member_bits(ty, layout).ok()?;
+
+    let members = children
+        .iter()
+        .zip(bits)
+        .map(|(m, bits)| UniqueHashMember {
+            name: m.name.clone(),
+            type_path: type_path_from_ty(&m.ty),
+            key: m.key,
+            bits,
+        })
+        .collect::<Vec<_>>();
+
+    let is_packed = matches!(layout, Layout::Fixed(_));
+    Some(compute_unique_hash(name, is_packed, &members))
+}
+
+/// Normalizes a Cairo type path, so formatting and import paths don't change
+/// the unique hash:
+/// - whitespaces and trailing commas are removed,
+/// - paths are reduced to their last segment (`starknet::ContractAddress` -> `ContractAddress`),
+/// - `Span` is replaced by `Array`, as both have the same schema,
+/// - generic arguments of custom types are removed (`Vec2<u32>` -> `Vec2`), as the schema
+///   only carries the name of structs and enums.
+///
+/// Type aliases can't be resolved from the source, so a member declared with an alias is
+/// hashed with the alias name, which [compute_unique_hash_from_ty] can't rebuild.
+pub fn normalize_type_path(type_path: &str) -> String {
+    let type_path = type_path
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    let mut normalized = String::new();
+    let mut segment = String::new();
+    // depth of the generic arguments of a custom type being skipped.
+    let mut skipped_depth = 0;
+
+    for c in type_path.chars() {
+        if skipped_depth > 0 {
+            match c {
+                '<' => skipped_depth += 1,
+                '>' => skipped_depth -= 1,
+                _ => {}
+            }
+        } else if c == '<' && !GENERIC_TYPES.contains(&normalize_path_segment(&segment).as_str()) {
+            normalized.push_str(&normalize_path_segment(&segment));
+            segment.clear();
+            skipped_depth = 1;
+        } else if TYPE_DELIMITERS.contains(&c) {
+            normalized.push_str(&normalize_path_segment(&segment));
+            segment.clear();
+
+            // trailing commas, like in single item tuples.
+            if (c == ')' || c == '>' || c == ']') && normalized.ends_with(',') {
+                normalized.pop();
+            }
+
+            normalized.push(c);
+        } else {
+            segment.push(c);
+        }
+    }
+
+    normalized.push_str(&normalize_path_segment(&segment));
+    normalized
+}
+
+fn normalize_path_segment(path: &str) -> String {
+    match path.rsplit("::").next().unwrap_or_default() {
+        "Span" => "Array".to_string(),
+        segment => segment.to_string(),
+    }
+}
+
+/// Builds the normalized type path of a [Ty], as it would be written in Cairo.
+pub fn type_path_from_ty(ty: &Ty) -> String {
+    match ty {
+        Ty::Primitive(p) => p.to_string(),
+        Ty::Struct(s) => s.name.clone(),
+        // the schema of `Option<T>` is an enum named `Option<T>`.
+        Ty::Enum(e) if e.name == "Option<T>" => format!(
+            "Option<{}>",
+            e.options
+                .first()
+                .map(|o| type_path_from_ty(&o.ty))
+                .unwrap_or_default()
+        ),
+        Ty::Enum(e) => e.name.clone(),
+        Ty::Tuple(tys) => format!(
+            "({})",
+            tys.iter()
+                .map(type_path_from_ty)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        Ty::Array(items) => format!(
+            "Array<{}>",
+            items.first().map(type_path_from_ty).unwrap_or_default()
+        ),
+        Ty::ByteArray(_) => "ByteArray".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitive::Primitive;
+    use crate::schema::Member;
+
+    fn member(name: &str, type_path: &str, key: bool) -> UniqueHashMember {
+        UniqueHashMember {
+            name: name.to_string(),
+            type_path: normalize_type_path(type_path),
+            key,
+            bits: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_type_path() {
+        assert_eq!(normalize_type_path("core::array::Array< u8 >"), "Array<u8>");
+        assert_eq!(normalize_type_path("Span<felt252>"), "Array<felt252>");
+        assert_eq!(
+            normalize_type_path("(u8, starknet::ContractAddress,)"),
+            "(u8,ContractAddress)"
+        );
+        assert_eq!(normalize_type_path("(u8,)"), "(u8)");
+        assert_eq!(normalize_type_path("Option<(u8, u16)>"), "Option<(u8,u16)>");
+        assert_eq!(normalize_type_path("Vec2<u32>"), "Vec2");
+        assert_eq!(
+            normalize_type_path("Span<(Vec2<Option<u8>>, u8)>"),
+            "Array<(Vec2,u8)>"
+        );
+    }
+
+    #[test]
+    fn test_unique_hash_covers_keys_and_order() {
+        let members = vec![
+            member("player", "ContractAddress", true),
+            member("x", "u32", false),
+        ];
+        let hash = compute_unique_hash("Position", false, &members);
+
+        let not_key = vec![
+            member("player", "ContractAddress", false),
+            member("x", "u32", false),
+        ];
+        assert_ne!(hash, compute_unique_hash("Position", false, &not_key));
+
+        let reordered = vec![
+            member("x", "u32", false),
+            member("player", "ContractAddress", true),
+        ];
+        assert_ne!(hash, compute_unique_hash("Position", false, &reordered));
+
+        let reformatted = vec![
+            member("player", "starknet::ContractAddress", true),
+            member("x", " u32 ", false),
+        ];
+        assert_eq!(hash, compute_unique_hash("Position", false, &reformatted));
+
+        assert_ne!(hash, compute_unique_hash("Position", true, &members));
+    }
+
+    #[test]
+    fn test_unique_hash_from_ty() {
+        let ty = Ty::Struct(Struct {
+            name: "Inventory".to_string(),
+            children: vec![
+                Member {
+                    name: "player".to_string(),
+                    ty: Ty::Primitive(Primitive::ContractAddress(None)),
+                    key: true,
+                },
+                Member {
+                    name: "items".to_string(),
+                    ty: Ty::Array(vec![Ty::Tuple(vec![
+                        Ty::Primitive(Primitive::U8(None)),
+                        Ty::Primitive(Primitive::U32(None)),
+                    ])]),
+                    key: false,
+                },
+            ],
+        });
+
+        let members = vec![
+            member("player", "starknet::ContractAddress", true),
+            member("items", "Span<(u8, u32)>", false),
+        ];
+
+        // the layout of the members is not used for models which are not packed.
+        assert_eq!(
+            compute_unique_hash_from_ty(&ty, &Layout::Struct(vec![])),
+            Some(compute_unique_hash("Inventory", false, &members))
+        );
+        assert_eq!(
+            compute_unique_hash_from_ty(&Ty::ByteArray("".to_string()), &Layout::ByteArray),
+            None
+        );
+    }
+
+    #[test]
+    fn test_unique_hash_from_ty_with_generic_members() {
+        let vec2 = Ty::Struct(Struct {
+            name: "Vec2".to_string(),
+            children: vec![
+                Member {
+                    name: "x".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(None)),
+                    key: false,
+                },
+                Member {
+                    name: "y".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(None)),
+                    key: false,
+                },
+            ],
+        });
+
+        let ty = Ty::Struct(Struct {
+            name: "Position".to_string(),
+            children: vec![
+                Member {
+                    name: "player".to_string(),
+                    ty: Ty::Primitive(Primitive::ContractAddress(None)),
+                    key: true,
+                },
+                Member {
+                    name: "vec".to_string(),
+                    ty: vec2.clone(),
+                    key: false,
+                },
+                Member {
+                    name: "path".to_string(),
+                    ty: Ty::Array(vec![vec2]),
+                    key: false,
+                },
+            ],
+        });
+
+        let members = vec![
+            member("player", "ContractAddress", true),
+            member("vec", "Vec2<u32>", false),
+            member("path", "Array<Vec2<u32>>", false),
+        ];
+
+        assert_eq!(
+            compute_unique_hash_from_ty(&ty, &Layout::Struct(vec![])),
+            Some(compute_unique_hash("Position", false, &members))
+        );
+    }
+
+    #[test]
+    fn test_unique_hash_from_ty_with_narrowed_members() {
+        let ty = Ty::Struct(Struct {
+            name: "Player".to_string(),
+            children: vec![
+                Member {
+                    name: "player".to_string(),
+                    ty: Ty::Primitive(Primitive::ContractAddress(None)),
+                    key: true,
+                },
+                Member {
+                    name: "x".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(None)),
+                    key: false,
+                },
+                Member {
+                    name: "level".to_string(),
+                    ty: Ty::Primitive(Primitive::U8(None)),
+                    key: false,
+                },
+            ],
+        });
+
+        let members = vec![
+            member("player", "starknet::ContractAddress", true),
+            member("x", "u32", false),
+            UniqueHashMember {
+                bits: Some(4),
+                ..member("level", "u8", false)
+            },
+        ];
+
+        assert_eq!(
+            compute_unique_hash_from_ty(&ty, &Layout::Fixed(vec![32, 4])),
+            Some(compute_unique_hash("Player", true, &members))
+        );
+        assert_eq!(
+            compute_unique_hash_from_ty(&ty, &Layout::Fixed(vec![32])),
+            None
+        );
+    }
+}
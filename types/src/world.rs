@@ -0,0 +1,1110 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem::discriminant;
+
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+use starknet::core::utils::get_selector_from_name;
+
+use crate::event::WorldEvent;
+use crate::layout::Layout;
+use crate::naming::{compute_bytearray_hash, compute_selector_from_names};
+use crate::packing::{deserialize_padded, PackingError};
+use crate::primitive::PrimitiveError;
+use crate::query::{EntityQuery, QueryError};
+use crate::schema::{Member, ModelMetadata, Ty};
+use crate::storage::entity_id_from_keys;
+
+/// The resource selector of the World itself, as used for World owners.
+pub const WORLD: Felt = Felt::ZERO;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorldError {
+    #[error("The namespace `{0}` is already registered.")]
+    NamespaceAlreadyRegistered(String),
+    #[error("The namespace `{0}` is not registered.")]
+    NamespaceNotRegistered(String),
+    #[error("The model {0:#x} is already registered.")]
+    ModelAlreadyRegistered(Felt),
+    #[error("The model {0:#x} is not registered.")]
+    ModelNotRegistered(Felt),
+    #[error("The member {member_selector:#x} doesn't exist in the model {model:#x}.")]
+    MemberNotFound { model: Felt, member_selector: Felt },
+    #[error("The schema of the model {0:#x} is not a struct.")]
+    InvalidSchema(Felt),
+    #[error("The model {model:#x} cannot be upgraded: {reason}.")]
+    IncompatibleUpgrade { model: Felt, reason: String },
+    #[error(transparent)]
+    Primitive(#[from] PrimitiveError),
+    #[error(transparent)]
+    Query(#[from] QueryError),
+    #[error(transparent)]
+    Packing(#[from] PackingError),
+}
+
+/// An in-memory World, holding the registered namespaces and models, the model
+/// entities and the resource permissions, to test off-chain logic without a node.
+///
+/// The state is updated by applying the events emitted by the World contract, and
+/// entities are read as [Ty] values of the model schemas.
+#[derive(Debug, Clone, Default)]
+pub struct World {
+    pub address: Felt,
+    /// The registered namespaces, by namespace hash.
+    namespaces: HashMap<Felt, String>,
+    /// The registered models, by model selector.
+    models: HashMap<Felt, ModelMetadata>,
+    /// The entities of each model, by model selector and entity id.
+    entities: HashMap<Felt, BTreeMap<Felt, Ty>>,
+    owners: HashSet<(Felt, Felt)>,
+    writers: HashSet<(Felt, Felt)>,
+}
+
+impl World {
+    /// Creates an empty World, owned by its creator.
+    pub fn new(address: Felt, creator: Felt) -> Self {
+        let mut world = Self {
+            address,
+            ..Default::default()
+        };
+        world.owners.insert((WORLD, creator));
+        world
+    }
+
+    /// Registers a namespace, owned by `owner`. Returns the namespace hash.
+    pub fn register_namespace(&mut self, namespace: &str, owner: Felt) -> Result<Felt, WorldError> {
+        let hash = compute_bytearray_hash(namespace);
+
+        if self.namespaces.contains_key(&hash) {
+            return Err(WorldError::NamespaceAlreadyRegistered(
+                namespace.to_string(),
+            ));
+        }
+
+        self.namespaces.insert(hash, namespace.to_string());
+        self.owners.insert((hash, owner));
+
+        Ok(hash)
+    }
+
+    /// Registers a model in its namespace, owned by `owner`. Returns the model selector.
+    pub fn register_model(
+        &mut self,
+        model: ModelMetadata,
+        owner: Felt,
+    ) -> Result<Felt, WorldError> {
+        if !self
+            .namespaces
+            .contains_key(&compute_bytearray_hash(&model.namespace))
+        {
+            return Err(WorldError::NamespaceNotRegistered(model.namespace));
+        }
+
+        let selector = compute_selector_from_names(&model.namespace, &model.name);
+
+        if self.models.contains_key(&selector) {
+            return Err(WorldError::ModelAlreadyRegistered(selector));
+        }
+
+        if model.schema.as_struct().is_none() {
+            return Err(WorldError::InvalidSchema(selector));
+        }
+
+        self.models.insert(selector, model);
+        self.owners.insert((selector, owner));
+
+        Ok(selector)
+    }
+
+    /// Upgrades a registered model to a new schema, and migrates its entities to it.
+    ///
+    /// Members, tuple items and enum variants are matched by name (or index for tuples),
+    /// and the ones added by the upgrade are zero-initialized, as they are read from
+    /// an empty storage by the World contract. Returns the model selector.
+    pub fn upgrade_model(&mut self, model: ModelMetadata) -> Result<Felt, WorldError> {
+        let selector = compute_selector_from_names(&model.namespace, &model.name);
+        self.model_schema(&selector)?;
+
+        if model.schema.as_struct().is_none() {
+            return Err(WorldError::InvalidSchema(selector));
+        }
+
+        if let Some(entities) = self.entities.get_mut(&selector) {
+            for entity in entities.values_mut() {
+                *entity = migrate_value(entity, &model.schema).map_err(|reason| {
+                    WorldError::IncompatibleUpgrade {
+                        model: selector,
+                        reason,
+                    }
+                })?;
+            }
+        }
+
+        self.models.insert(selector, model);
+
+        Ok(selector)
+    }
+
+    /// Returns the namespace registered with the given hash.
+    pub fn namespace(&self, hash: &Felt) -> Option<&str> {
+        self.namespaces.get(hash).map(String::as_str)
+    }
+
+    /// Returns the metadata of a registered model.
+    pub fn model(&self, selector: &Felt) -> Option<&ModelMetadata> {
+        self.models.get(selector)
+    }
+
+    /// Applies an event emitted by the World contract to the World state.
+    ///
+    /// Events are trusted: permissions are not checked, as the World contract already
+    /// did it before emitting them.
+    pub fn apply_event(&mut self, event: &WorldEvent) -> Result<(), WorldError> {
+        match event {
+            WorldEvent::NamespaceRegistered(e) => {
+                self.namespaces.insert(e.hash, e.namespace.clone());
+            }
+            WorldEvent::StoreSetRecord(e) => {
+                let mut entity = self.model_schema(&e.selector)?.clone();
+                deserialize_members(&mut entity, |m| m.key, &e.keys)?;
+                self.deserialize_values(&e.selector, &mut entity, &e.values)?;
+
+                self.entities
+                    .entry(e.selector)
+                    .or_default()
+                    .insert(e.entity_id, entity);
+            }
+            WorldEvent::StoreUpdateRecord(e) => {
+                let mut entity = self.model_schema(&e.selector)?.clone();
+                if let Some(existing) = self.entity(&e.selector, &e.entity_id) {
+                    copy_keys(existing, &mut entity);
+                }
+                self.deserialize_values(&e.selector, &mut entity, &e.values)?;
+
+                self.entities
+                    .entry(e.selector)
+                    .or_default()
+                    .insert(e.entity_id, entity);
+            }
+            WorldEvent::StoreUpdateMember(e) => {
+                let is_member =
+                    |m: &Member| get_selector_from_name(&m.name).ok() == Some(e.member_selector);
+
+                let mut value = self
+                    .model_schema(&e.selector)?
+                    .as_struct()
+                    .and_then(|s| s.children.iter().find(|m| is_member(m)))
+                    .map(|m| m.ty.clone())
+                    .ok_or(WorldError::MemberNotFound {
+                        model: e.selector,
+                        member_selector: e.member_selector,
+                    })?;
+                let member_layout = match self.model_layout(&e.selector)? {
+                    Some(Layout::Struct(fields)) => fields
+                        .into_iter()
+                        .find(|f| f.selector == e.member_selector)
+                        .map(|f| f.layout),
+                    _ => None,
+                };
+
+                match member_layout {
+                    Some(layout) => deserialize_padded(&mut value, &layout, &mut e.values.clone())?,
+                    None => value.deserialize(&mut e.values.clone())?,
+                }
+
+                let mut entity = self.read_entity(&e.selector, &e.entity_id)?;
+                if let Some(member) = entity
+                    .as_struct_mut()
+                    .and_then(|s| s.children.iter_mut().find(|m| is_member(m)))
+                {
+                    member.ty = value;
+                }
+
+                self.entities
+                    .entry(e.selector)
+                    .or_default()
+                    .insert(e.entity_id, entity);
+            }
+            WorldEvent::StoreDelRecord(e) => {
+                self.model_schema(&e.selector)?;
+
+                if let Some(entities) = self.entities.get_mut(&e.selector) {
+                    entities.remove(&e.entity_id);
+                }
+            }
+            WorldEvent::WriterUpdated(e) => {
+                update_permission(&mut self.writers, e.resource, e.contract, e.value)
+            }
+            WorldEvent::OwnerUpdated(e) => {
+                update_permission(&mut self.owners, e.resource, e.contract, e.value)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the entity of a model stored with the given entity id, if any.
+    pub fn entity(&self, model: &Felt, entity_id: &Felt) -> Option<&Ty> {
+        self.entities
+            .get(model)
+            .and_then(|entities| entities.get(entity_id))
+    }
+
+    /// Returns the entity of a model stored with the given serialized keys, if any.
+    pub fn entity_from_keys(&self, model: &Felt, keys: &[Felt]) -> Option<&Ty> {
+        self.entity(model, &entity_id_from_keys(keys))
+    }
+
+    /// Returns the entities of a model, ordered by entity id.
+    pub fn entities(&self, model: &Felt) -> impl Iterator<Item = (&Felt, &Ty)> {
+        self.entities.get(model).into_iter().flatten()
+    }
+
+    /// Returns the entities of a model matching the query.
+    pub fn query(&self, model: &Felt, query: &EntityQuery) -> Result<Vec<&Ty>, WorldError> {
+        self.model_schema(model)?;
+        Ok(query.filter(self.entities(model).map(|(_, entity)| entity))?)
+    }
+
+    /// Returns true if the contract is an owner of the resource.
+    pub fn is_owner(&self, resource: &Felt, contract: &Felt) -> bool {
+        self.owners.contains(&(*resource, *contract))
+    }
+
+    /// Returns true if the contract is a writer of the resource.
+    pub fn is_writer(&self, resource: &Felt, contract: &Felt) -> bool {
+        self.writers.contains(&(*resource, *contract))
+    }
+
+    /// Returns true if the contract can write the model, following the permissions
+    /// hierarchy of the World contract: writer or owner of the model, owner of the
+    /// World, or writer or owner of the model namespace.
+    pub fn can_write_model(&self, model: &Felt, contract: &Felt) -> bool {
+        if self.is_writer(model, contract)
+            || self.is_owner(model, contract)
+            || self.is_owner(&WORLD, contract)
+        {
+            return true;
+        }
+
+        self.models.get(model).is_some_and(|m| {
+            let namespace_hash = compute_bytearray_hash(&m.namespace);
+            self.is_writer(&namespace_hash, contract) || self.is_owner(&namespace_hash, contract)
+        })
+    }
+
+    /// Returns a serializable snapshot of the World state.
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let mut namespaces = self
+            .namespaces
+            .iter()
+            .map(|(h, n)| (*h, n.clone()))
+            .collect::<Vec<_>>();
+        namespaces.sort();
+
+        let mut models = self.models.iter().collect::<Vec<_>>();
+        models.sort_by_key(|(selector, _)| **selector);
+
+        let entities = models
+            .iter()
+            .flat_map(|(selector, _)| {
+                self.entities(selector)
+                    .map(|(entity_id, entity)| EntitySnapshot {
+                        model: **selector,
+                        entity_id: *entity_id,
+                        entity: entity.clone(),
+                    })
+            })
+            .collect();
+
+        let sorted = |permissions: &HashSet<(Felt, Felt)>| {
+            let mut permissions = permissions.iter().copied().collect::<Vec<_>>();
+            permissions.sort();
+            permissions
+        };
+
+        WorldSnapshot {
+            address: self.address,
+            namespaces,
+            models: models.into_iter().map(|(_, m)| m.clone()).collect(),
+            entities,
+            owners: sorted(&self.owners),
+            writers: sorted(&self.writers),
+        }
+    }
+
+    /// Restores a World from a snapshot.
+    pub fn from_snapshot(snapshot: WorldSnapshot) -> Self {
+        let mut world = Self {
+            address: snapshot.address,
+            namespaces: snapshot.namespaces.into_iter().collect(),
+            owners: snapshot.owners.into_iter().collect(),
+            writers: snapshot.writers.into_iter().collect(),
+            ..Default::default()
+        };
+
+        for model in snapshot.models {
+            world.models.insert(
+                compute_selector_from_names(&model.namespace, &model.name),
+                model,
+            );
+        }
+
+        for e in snapshot.entities {
+            world
+                .entities
+                .entry(e.model)
+                .or_default()
+                .insert(e.entity_id, e.entity);
+        }
+
+        world
+    }
+
+    fn model_schema(&self, model: &Felt) -> Result<&Ty, WorldError> {
+        self.models
+            .get(model)
+            .map(|m| &m.schema)
+            .ok_or(WorldError::ModelNotRegistered(*model))
+    }
+
+    /// Returns the layout of a model, if it has been provided at registration.
+    fn model_layout(&self, model: &Felt) -> Result<Option<Layout>, WorldError> {
+        let metadata = self
+            .models
+            .get(model)
+            .ok_or(WorldError::ModelNotRegistered(*model))?;
+
+        if metadata.layout.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            Layout::from_felts(&metadata.layout).map_err(PackingError::from)?,
+        ))
+    }
+
+    /// Deserializes the values of a model entity, as emitted by the World contract.
+    ///
+    /// With the model layout, the padding of the packed enums is skipped as the World
+    /// pads their data up to the size of their largest variant.
+    fn deserialize_values(
+        &self,
+        model: &Felt,
+        entity: &mut Ty,
+        values: &[Felt],
+    ) -> Result<(), WorldError> {
+        match self.model_layout(model)? {
+            Some(layout) => deserialize_padded(entity, &layout, &mut values.to_vec())?,
+            None => deserialize_members(entity, |m| !m.key, values)?,
+        }
+
+        Ok(())
+    }
+
+    /// Returns a copy of an entity, or the model schema if the entity doesn't exist yet,
+    /// as the World contract updates the values of an entity even if it has never been set.
+    ///
+    /// The values of an event must not be deserialized into the returned entity, but into
+    /// the model schema: an array value only holds its items and not the item type.
+    fn read_entity(&self, model: &Felt, entity_id: &Felt) -> Result<Ty, WorldError> {
+        match self.entity(model, entity_id) {
+            Some(entity) => Ok(entity.clone()),
+            None => Ok(self.model_schema(model)?.clone()),
+        }
+    }
+}
+
+/// A serializable snapshot of the World state, in a deterministic order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub address: Felt,
+    pub namespaces: Vec<(Felt, String)>,
+    pub models: Vec<ModelMetadata>,
+    pub entities: Vec<EntitySnapshot>,
+    pub owners: Vec<(Felt, Felt)>,
+    pub writers: Vec<(Felt, Felt)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    pub model: Felt,
+    pub entity_id: Felt,
+    pub entity: Ty,
+}
+
+/// Migrates a value to a new schema, keeping the values of the members, tuple items
+/// and enum variants existing in both, and zero-initializing the new ones.
+fn migrate_value(value: &Ty, schema: &Ty) -> Result<Ty, String> {
+    let migrated = match (value, schema) {
+        (Ty::Primitive(v), Ty::Primitive(s)) => {
+            if discriminant(v) == discriminant(s) {
+                return Ok(value.clone());
+            }
+
+            // an unset value stays unset.
+            let mut migrated = *s;
+            if let Ok(mut felts) = v.serialize() {
+                migrated
+                    .deserialize(&mut felts)
+                    .map_err(|e| format!("the value of `{v}` doesn't fit in `{s}` ({e})"))?;
+            }
+
+            Ty::Primitive(migrated)
+        }
+        (Ty::Struct(v), Ty::Struct(s)) => {
+            let mut migrated = s.clone();
+
+            for member in &mut migrated.children {
+                member.ty = match v.get(&member.name) {
+                    Some(old) => migrate_value(old, &member.ty)?,
+                    None => zero_value(&member.ty)?,
+                };
+            }
+
+            Ty::Struct(migrated)
+        }
+        (Ty::Enum(v), Ty::Enum(s)) => {
+            let mut migrated = s.clone();
+
+            if let Ok(old) = v.option() {
+                let index = s
+                    .options
+                    .iter()
+                    .position(|o| o.name == old.name)
+                    .ok_or_else(|| format!("the variant `{}` has been removed", old.name))?;
+
+                migrated.option = Some(index as u8);
+                migrated.options[index].ty = migrate_value(&old.ty, &s.options[index].ty)?;
+            }
+
+            Ty::Enum(migrated)
+        }
+        (Ty::Tuple(v), Ty::Tuple(s)) if v.len() <= s.len() => Ty::Tuple(
+            s.iter()
+                .enumerate()
+                .map(|(i, item)| match v.get(i) {
+                    Some(old) => migrate_value(old, item),
+                    None => zero_value(item),
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        (Ty::Array(v), Ty::Array(s)) => {
+            let item = s.first().ok_or("the array item type is missing")?;
+            Ty::Array(
+                v.iter()
+                    .map(|old| migrate_value(old, item))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        (Ty::ByteArray(_), Ty::ByteArray(_)) => value.clone(),
+        _ => {
+            return Err(format!(
+                "the type `{}` cannot be changed to `{}`",
+                value.name(),
+                schema.name()
+            ))
+        }
+    };
+
+    Ok(migrated)
+}
+
+/// Returns the value of a type read from an empty storage.
+///
+/// The item type of an array is only kept by the schema, as an array value only holds
+/// its items, but it must exist to migrate or deserialize the array items later on.
+fn zero_value(schema: &Ty) -> Result<Ty, String> {
+    let value = match schema {
+        Ty::Primitive(p) => {
+            let mut value = *p;
+            value
+                .deserialize(&mut vec![Felt::ZERO; 2])
+                .map_err(|e| e.to_string())?;
+            Ty::Primitive(value)
+        }
+        Ty::Struct(s) => {
+            let mut value = s.clone();
+            for member in &mut value.children {
+                member.ty = zero_value(&member.ty)?;
+            }
+            Ty::Struct(value)
+        }
+        Ty::Tuple(items) => Ty::Tuple(items.iter().map(zero_value).collect::<Result<_, _>>()?),
+        Ty::Enum(e) => {
+            let mut value = e.clone();
+            let first = value
+                .options
+                .first_mut()
+                .ok_or_else(|| format!("the enum `{}` has no variant", e.name))?;
+            first.ty = zero_value(&first.ty)?;
+            value.option = Some(0);
+            Ty::Enum(value)
+        }
+        Ty::Array(items) => {
+            items.first().ok_or("the array item type is missing")?;
+            Ty::Array(vec![])
+        }
+        Ty::ByteArray(_) => Ty::ByteArray(String::new()),
+    };
+
+    Ok(value)
+}
+
+/// Copies the key members of an entity into another entity of the same model.
+fn copy_keys(from: &Ty, to: &mut Ty) {
+    if let (Some(from), Some(to)) = (from.as_struct(), to.as_struct_mut()) {
+        for (from, to) in from.children.iter().zip(to.children.iter_mut()) {
+            if to.key {
+                to.ty = from.ty.clone();
+            }
+        }
+    }
+}
+
+/// Deserializes the given values into the filtered members of a model entity.
+fn deserialize_members(
+    entity: &mut Ty,
+    filter: impl Fn(&Member) -> bool,
+    values: &[Felt],
+) -> Result<(), WorldError> {
+    let mut values = values.to_vec();
+
+    if let Some(model) = entity.as_struct_mut() {
+        for member in model.children.iter_mut().filter(|m| filter(m)) {
+            member.ty.deserialize(&mut values)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn update_permission(
+    permissions: &mut HashSet<(Felt, Felt)>,
+    resource: Felt,
+    contract: Felt,
+    value: bool,
+) {
+    if value {
+        permissions.insert((resource, contract));
+    } else {
+        permissions.remove(&(resource, contract));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{
+        OwnerUpdated, StoreUpdateMember, StoreUpdateRecord, WorldStoreDelRecord,
+        WorldStoreSetRecord, WriterUpdated,
+    };
+    use crate::primitive::Primitive;
+    use crate::query::{Clause, ComparisonOperator, MemberClause, MemberValue};
+    use crate::schema::{Enum, EnumOption, Struct};
+
+    fn position_model() -> ModelMetadata {
+        let member = |name: &str, key: bool| Member {
+            name: name.to_string(),
+            ty: Ty::Primitive(Primitive::U32(None)),
+            key,
+        };
+
+        ModelMetadata {
+            schema: Ty::Struct(Struct {
+                name: "Position".to_string(),
+                children: vec![
+                    member("player", true),
+                    member("x", false),
+                    member("y", false),
+                ],
+            }),
+            namespace: "ns".to_string(),
+            name: "Position".to_string(),
+            packed_size: 2,
+            unpacked_size: 2,
+            class_hash: Felt::ZERO,
+            contract_address: Felt::ZERO,
+            layout: vec![],
+        }
+    }
+
+    fn member_value(entity: &Ty, name: &str) -> Primitive {
+        *entity
+            .as_struct()
+            .unwrap()
+            .get(name)
+            .unwrap()
+            .as_primitive()
+            .unwrap()
+    }
+
+    fn world_with_model() -> (World, Felt) {
+        let mut world = World::new(Felt::ONE, Felt::TWO);
+        world.register_namespace("ns", Felt::TWO).unwrap();
+        let model = world.register_model(position_model(), Felt::TWO).unwrap();
+        (world, model)
+    }
+
+    #[test]
+    fn test_register_resources() {
+        let (mut world, model) = world_with_model();
+
+        assert_eq!(model, compute_selector_from_names("ns", "Position"));
+        assert_eq!(world.namespace(&compute_bytearray_hash("ns")), Some("ns"));
+        assert!(world.register_namespace("ns", Felt::TWO).is_err());
+        assert!(matches!(
+            world.register_model(position_model(), Felt::TWO),
+            Err(WorldError::ModelAlreadyRegistered(_))
+        ));
+
+        let model = ModelMetadata {
+            namespace: "other".to_string(),
+            ..position_model()
+        };
+        assert!(matches!(
+            world.register_model(model, Felt::TWO),
+            Err(WorldError::NamespaceNotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_store_events() {
+        let (mut world, model) = world_with_model();
+        let keys = vec![Felt::from(7)];
+        let entity_id = entity_id_from_keys(&keys);
+
+        world
+            .apply_event(&WorldEvent::StoreSetRecord(WorldStoreSetRecord {
+                selector: model,
+                entity_id,
+                keys: keys.clone(),
+                values: vec![Felt::from(1), Felt::from(2)],
+            }))
+            .unwrap();
+
+        let entity = world.entity_from_keys(&model, &keys).unwrap();
+        assert_eq!(member_value(entity, "player"), Primitive::U32(Some(7)));
+        assert_eq!(member_value(entity, "y"), Primitive::U32(Some(2)));
+
+        world
+            .apply_event(&WorldEvent::StoreUpdateRecord(StoreUpdateRecord {
+                selector: model,
+                entity_id,
+                values: vec![Felt::from(3), Felt::from(4)],
+            }))
+            .unwrap();
+        world
+            .apply_event(&WorldEvent::StoreUpdateMember(StoreUpdateMember {
+                selector: model,
+                entity_id,
+                member_selector: get_selector_from_name("y").unwrap(),
+                values: vec![Felt::from(5)],
+            }))
+            .unwrap();
+
+        let entity = world.entity(&model, &entity_id).unwrap();
+        assert_eq!(member_value(entity, "player"), Primitive::U32(Some(7)));
+        assert_eq!(member_value(entity, "x"), Primitive::U32(Some(3)));
+        assert_eq!(member_value(entity, "y"), Primitive::U32(Some(5)));
+
+        let query = EntityQuery {
+            clause: Some(Clause::Member(MemberClause {
+                path: "x".to_string(),
+                operator: ComparisonOperator::Eq,
+                value: MemberValue::Primitive(Primitive::U32(Some(3))),
+            })),
+            ..Default::default()
+        };
+        assert_eq!(world.query(&model, &query).unwrap().len(), 1);
+
+        assert!(matches!(
+            world.apply_event(&WorldEvent::StoreUpdateMember(StoreUpdateMember {
+                selector: model,
+                entity_id,
+                member_selector: get_selector_from_name("z").unwrap(),
+                values: vec![Felt::from(5)],
+            })),
+            Err(WorldError::MemberNotFound { .. })
+        ));
+
+        world
+            .apply_event(&WorldEvent::StoreDelRecord(WorldStoreDelRecord {
+                selector: model,
+                entity_id,
+            }))
+            .unwrap();
+        assert!(world.entity(&model, &entity_id).is_none());
+        assert_eq!(world.entities(&model).count(), 0);
+
+        assert!(matches!(
+            world.apply_event(&WorldEvent::StoreDelRecord(WorldStoreDelRecord {
+                selector: Felt::THREE,
+                entity_id,
+            })),
+            Err(WorldError::ModelNotRegistered(_))
+        ));
+    }
+
+    fn fighter_model() -> ModelMetadata {
+        let option = |name: &str, ty: Ty| EnumOption {
+            name: name.to_string(),
+            ty,
+        };
+        let u32_ty = || Ty::Primitive(Primitive::U32(None));
+
+        let state = Ty::Enum(Enum {
+            name: "State".to_string(),
+            option: None,
+            options: vec![
+                option("Idle", Ty::Tuple(vec![])),
+                option("Moving", Ty::Tuple(vec![u32_ty(), u32_ty()])),
+                option("Attacking", u32_ty()),
+            ],
+        });
+
+        // the Cairo serialization of the model layout, a struct layout with the
+        // fixed layouts of `state` and `level`.
+        let layout = [
+            vec![Felt::ONE, Felt::TWO],
+            vec![
+                get_selector_from_name("state").unwrap(),
+                Felt::ZERO,
+                Felt::THREE,
+            ],
+            vec![Felt::from(8), Felt::from(32), Felt::from(32)],
+            vec![
+                get_selector_from_name("level").unwrap(),
+                Felt::ZERO,
+                Felt::ONE,
+            ],
+            vec![Felt::from(8)],
+        ]
+        .concat();
+
+        ModelMetadata {
+            schema: Ty::Struct(Struct {
+                name: "Fighter".to_string(),
+                children: vec![
+                    Member {
+                        name: "id".to_string(),
+                        ty: u32_ty(),
+                        key: true,
+                    },
+                    Member {
+                        name: "state".to_string(),
+                        ty: state,
+                        key: false,
+                    },
+                    Member {
+                        name: "level".to_string(),
+                        ty: Ty::Primitive(Primitive::U8(None)),
+                        key: false,
+                    },
+                ],
+            }),
+            namespace: "ns".to_string(),
+            name: "Fighter".to_string(),
+            packed_size: 4,
+            unpacked_size: 4,
+            class_hash: Felt::ZERO,
+            contract_address: Felt::ZERO,
+            layout,
+        }
+    }
+
+    #[test]
+    fn test_apply_store_events_with_padded_enums() {
+        let mut world = World::new(Felt::ONE, Felt::TWO);
+        world.register_namespace("ns", Felt::TWO).unwrap();
+        let model = world.register_model(fighter_model(), Felt::TWO).unwrap();
+
+        let keys = vec![Felt::from(7)];
+        let entity_id = entity_id_from_keys(&keys);
+
+        // the data of `Attacking` are padded up to the size of `Moving`.
+        world
+            .apply_event(&WorldEvent::StoreSetRecord(WorldStoreSetRecord {
+                selector: model,
+                entity_id,
+                keys,
+                values: vec![Felt::TWO, Felt::from(5), Felt::ZERO, Felt::THREE],
+            }))
+            .unwrap();
+
+        let entity = world.entity(&model, &entity_id).unwrap();
+        let state = entity.as_struct().unwrap().get("state").unwrap();
+        assert_eq!(state.as_enum().unwrap().option, Some(2));
+        assert_eq!(member_value(entity, "level"), Primitive::U8(Some(3)));
+
+        world
+            .apply_event(&WorldEvent::StoreUpdateMember(StoreUpdateMember {
+                selector: model,
+                entity_id,
+                member_selector: get_selector_from_name("state").unwrap(),
+                values: vec![Felt::ZERO, Felt::ZERO, Felt::ZERO],
+            }))
+            .unwrap();
+
+        let entity = world.entity(&model, &entity_id).unwrap();
+        let state = entity.as_struct().unwrap().get("state").unwrap();
+        assert_eq!(state.as_enum().unwrap().option, Some(0));
+        assert_eq!(member_value(entity, "level"), Primitive::U8(Some(3)));
+
+        world
+            .apply_event(&WorldEvent::StoreUpdateRecord(StoreUpdateRecord {
+                selector: model,
+                entity_id,
+                values: vec![Felt::ONE, Felt::from(1), Felt::from(2), Felt::from(4)],
+            }))
+            .unwrap();
+
+        let entity = world.entity(&model, &entity_id).unwrap();
+        let state = entity.as_struct().unwrap().get("state").unwrap();
+        assert_eq!(state.as_enum().unwrap().option, Some(1));
+        assert_eq!(member_value(entity, "level"), Primitive::U8(Some(4)));
+    }
+
+    fn inventory_model() -> ModelMetadata {
+        ModelMetadata {
+            schema: Ty::Struct(Struct {
+                name: "Inventory".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        ty: Ty::Primitive(Primitive::U32(None)),
+                        key: true,
+                    },
+                    Member {
+                        name: "items".to_string(),
+                        ty: Ty::Array(vec![Ty::Primitive(Primitive::U32(None))]),
+                        key: false,
+                    },
+                    Member {
+                        name: "state".to_string(),
+                        ty: Ty::Enum(Enum {
+                            name: "State".to_string(),
+                            option: None,
+                            options: vec![
+                                EnumOption {
+                                    name: "Idle".to_string(),
+                                    ty: Ty::Tuple(vec![]),
+                                },
+                                EnumOption {
+                                    name: "Moving".to_string(),
+                                    ty: Ty::Primitive(Primitive::U32(None)),
+                                },
+                            ],
+                        }),
+                        key: false,
+                    },
+                    Member {
+                        name: "name".to_string(),
+                        ty: Ty::ByteArray("".to_string()),
+                        key: false,
+                    },
+                ],
+            }),
+            namespace: "ns".to_string(),
+            name: "Inventory".to_string(),
+            packed_size: 0,
+            unpacked_size: 0,
+            class_hash: Felt::ZERO,
+            contract_address: Felt::ZERO,
+            layout: vec![],
+        }
+    }
+
+    fn items(entity: &Ty) -> Vec<Primitive> {
+        entity
+            .as_struct()
+            .unwrap()
+            .get("items")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| *item.as_primitive().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_store_events_with_dynamic_members() {
+        let mut world = World::new(Felt::ONE, Felt::TWO);
+        world.register_namespace("ns", Felt::TWO).unwrap();
+        let model = world.register_model(inventory_model(), Felt::TWO).unwrap();
+
+        let keys = vec![Felt::from(7)];
+        let entity_id = entity_id_from_keys(&keys);
+        let state = |entity: &Ty| {
+            entity
+                .get("state")
+                .unwrap()
+                .as_enum()
+                .unwrap()
+                .to_sql_value()
+        };
+        let name = |entity: &Ty| entity.get("name").unwrap().as_byte_array().unwrap().clone();
+
+        // items: [10, 20], state: Moving(5), name: "ab".
+        world
+            .apply_event(&WorldEvent::StoreSetRecord(WorldStoreSetRecord {
+                selector: model,
+                entity_id,
+                keys: keys.clone(),
+                values: [2, 10, 20, 1, 5, 0, 0x6162, 2].map(Felt::from).to_vec(),
+            }))
+            .unwrap();
+
+        // items: [30], state: Idle, name: "c".
+        world
+            .apply_event(&WorldEvent::StoreUpdateRecord(StoreUpdateRecord {
+                selector: model,
+                entity_id,
+                values: [1, 30, 0, 0, 0x63, 1].map(Felt::from).to_vec(),
+            }))
+            .unwrap();
+
+        let entity = world.entity(&model, &entity_id).unwrap();
+        assert_eq!(member_value(entity, "player"), Primitive::U32(Some(7)));
+        assert_eq!(items(entity), vec![Primitive::U32(Some(30))]);
+        assert_eq!(state(entity), "Idle");
+        assert_eq!(name(entity), "c");
+
+        let update_member = |world: &mut World, member: &str, values: &[u32]| {
+            world
+                .apply_event(&WorldEvent::StoreUpdateMember(StoreUpdateMember {
+                    selector: model,
+                    entity_id,
+                    member_selector: get_selector_from_name(member).unwrap(),
+                    values: values.iter().map(|v| Felt::from(*v)).collect(),
+                }))
+                .unwrap();
+        };
+
+        // an empty array can be updated again.
+        update_member(&mut world, "items", &[0]);
+        assert!(items(world.entity(&model, &entity_id).unwrap()).is_empty());
+
+        update_member(&mut world, "items", &[2, 1, 2]);
+        update_member(&mut world, "state", &[1, 9]);
+        update_member(&mut world, "name", &[0, 0, 0]);
+
+        let entity = world.entity(&model, &entity_id).unwrap();
+        assert_eq!(member_value(entity, "player"), Primitive::U32(Some(7)));
+        assert_eq!(
+            items(entity),
+            vec![Primitive::U32(Some(1)), Primitive::U32(Some(2))]
+        );
+        assert_eq!(state(entity), "Moving");
+        assert_eq!(name(entity), "");
+    }
+
+    #[test]
+    fn test_upgrade_model_with_array_members() {
+        let mut world = World::new(Felt::ONE, Felt::TWO);
+        world.register_namespace("ns", Felt::TWO).unwrap();
+        let model = world.register_model(inventory_model(), Felt::TWO).unwrap();
+
+        let set_record = |world: &mut World, player: u32, values: &[u32]| {
+            world
+                .apply_event(&WorldEvent::StoreSetRecord(WorldStoreSetRecord {
+                    selector: model,
+                    entity_id: entity_id_from_keys(&[Felt::from(player)]),
+                    keys: vec![Felt::from(player)],
+                    values: values.iter().map(|v| Felt::from(*v)).collect(),
+                }))
+                .unwrap();
+        };
+        set_record(&mut world, 1, &[2, 10, 20, 0, 0, 0, 0]);
+        set_record(&mut world, 2, &[0, 0, 0, 0, 0]);
+
+        // the items are widened to u64, and an array member is added.
+        let mut upgraded = inventory_model();
+        if let Ty::Struct(s) = &mut upgraded.schema {
+            s.children[1].ty = Ty::Array(vec![Ty::Primitive(Primitive::U64(None))]);
+            s.children.push(Member {
+                name: "tags".to_string(),
+                ty: Ty::Array(vec![Ty::Primitive(Primitive::U8(None))]),
+                key: false,
+            });
+        }
+        world.upgrade_model(upgraded.clone()).unwrap();
+
+        let entity = world.entity_from_keys(&model, &[Felt::ONE]).unwrap();
+        assert_eq!(
+            items(entity),
+            vec![Primitive::U64(Some(10)), Primitive::U64(Some(20))]
+        );
+        assert_eq!(entity.get("tags").unwrap(), &Ty::Array(vec![]));
+        assert!(items(world.entity_from_keys(&model, &[Felt::TWO]).unwrap()).is_empty());
+
+        // the upgraded entities are updated with the item types of the new schema.
+        let entity_id = entity_id_from_keys(&[Felt::TWO]);
+        world
+            .apply_event(&WorldEvent::StoreUpdateRecord(StoreUpdateRecord {
+                selector: model,
+                entity_id,
+                values: [1, 30, 0, 0, 0, 0, 2, 4, 5].map(Felt::from).to_vec(),
+            }))
+            .unwrap();
+
+        let entity = world.entity(&model, &entity_id).unwrap();
+        assert_eq!(items(entity), vec![Primitive::U64(Some(30))]);
+        assert_eq!(
+            entity.get("tags").unwrap(),
+            &Ty::Array(vec![
+                Ty::Primitive(Primitive::U8(Some(4))),
+                Ty::Primitive(Primitive::U8(Some(5)))
+            ])
+        );
+
+        // upgrading again migrates the empty arrays as well.
+        if let Ty::Struct(s) = &mut upgraded.schema {
+            s.children[4].ty = Ty::Array(vec![Ty::Primitive(Primitive::U16(None))]);
+        }
+        world.upgrade_model(upgraded).unwrap();
+
+        let entity = world.entity_from_keys(&model, &[Felt::ONE]).unwrap();
+        assert_eq!(entity.get("tags").unwrap(), &Ty::Array(vec![]));
+
+        // an array without item type can't be zero-initialized.
+        assert!(zero_value(&Ty::Array(vec![])).is_err());
+    }
+
+    #[test]
+    fn test_permissions() {
+        let (mut world, model) = world_with_model();
+        let (world_owner, contract) = (Felt::TWO, Felt::from(0x1234));
+
+        assert!(world.is_owner(&WORLD, &world_owner));
+        assert!(world.can_write_model(&model, &world_owner));
+        assert!(!world.can_write_model(&model, &contract));
+
+        world
+            .apply_event(&WorldEvent::WriterUpdated(WriterUpdated {
+                resource: compute_bytearray_hash("ns"),
+                contract,
+                value: true,
+            }))
+            .unwrap();
+        assert!(world.can_write_model(&model, &contract));
+        assert!(!world.is_writer(&model, &contract));
+
+        world
+            .apply_event(&WorldEvent::WriterUpdated(WriterUpdated {
+                resource: compute_bytearray_hash("ns"),
+                contract,
+                value: false,
+            }))
+            .unwrap();
+        world
+            .apply_event(&WorldEvent::OwnerUpdated(OwnerUpdated {
+                resource: model,
+                contract,
+                value: true,
+            }))
+            .unwrap();
+        assert!(world.is_owner(&model, &contract));
+        assert!(world.can_write_model(&model, &contract));
+    }
+}